@@ -0,0 +1,9 @@
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::main]
+async fn main() {
+    let args = std::env::args().skip(1).collect();
+    std::process::exit(catboard_discord_hook::cli::run(args).await);
+}
+
+#[cfg(target_arch = "wasm32")]
+fn main() {}
@@ -1,2 +1,11 @@
+pub(crate) mod alert;
+pub(crate) mod chart;
 pub(crate) mod consumer;
+pub(crate) mod convert;
+pub(crate) mod history;
+pub(crate) mod indicators;
+pub(crate) mod migration;
+pub(crate) mod price_cache;
 pub(crate) mod publisher;
+pub(crate) mod wallet;
+pub(crate) mod watchlist;
@@ -0,0 +1,392 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+use crate::{
+    catboard::consumer::{get_pair_by_symbol, get_symbols_from_kv},
+    discord::rest::DiscordClient,
+    error::Error,
+    kv::{
+        bindings::Bindings,
+        json::{get_json, put_json, Versioned},
+        keys, KeyValueStore, PutOptions,
+    },
+    solana::web3::Cluster,
+};
+
+const MAX_ALERTS_PER_USER: usize = 10;
+/// Alerts are kept for a year.
+const ALERT_TTL: u64 = 60 * 60 * 24 * 365;
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum AlertDirection {
+    Above,
+    Below,
+}
+
+impl FromStr for AlertDirection {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_lowercase().as_str() {
+            "above" => Ok(AlertDirection::Above),
+            "below" => Ok(AlertDirection::Below),
+            _ => Err(Error::InvalidPayload(
+                "direction must be `above` or `below`".to_string(),
+            )),
+        }
+    }
+}
+
+impl fmt::Display for AlertDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlertDirection::Above => write!(f, "above"),
+            AlertDirection::Below => write!(f, "below"),
+        }
+    }
+}
+
+/// An alert of one user, kept on its own at `alert:Crypto.SOL/USD:1234:SOL-1`.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub(crate) struct PriceAlert {
+    pub(crate) id: String,
+    pub(crate) symbol: String,
+    pub(crate) direction: AlertDirection,
+    pub(crate) price: f64,
+    pub(crate) user_id: String,
+    pub(crate) channel_id: String,
+    /// Arm again once the price is back on the other side of the target.
+    #[serde(default)]
+    pub(crate) rearm: bool,
+    #[serde(default)]
+    pub(crate) fired: bool,
+    #[serde(default)]
+    pub(crate) fired_at: Option<String>,
+}
+
+impl PriceAlert {
+    fn is_crossed(&self, price: f64) -> bool {
+        match self.direction {
+            AlertDirection::Above => price >= self.price,
+            AlertDirection::Below => price <= self.price,
+        }
+    }
+
+    /// Update the state with the latest `price`, `true` when the alert just fired.
+    pub(crate) fn check(&mut self, price: f64) -> bool {
+        match (self.fired, self.is_crossed(price)) {
+            (false, true) => {
+                self.fired = true;
+                self.fired_at = Some(Utc::now().to_rfc3339());
+                true
+            }
+            (true, false) if self.rearm => {
+                self.fired = false;
+                false
+            }
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for PriceAlert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "`{}` `{}` {} `${:.2}`",
+            self.id, self.symbol, self.direction, self.price
+        )?;
+        if self.rearm {
+            write!(f, " 🔁")?;
+        }
+        if self.fired {
+            write!(f, " (fired)")?;
+        }
+        Ok(())
+    }
+}
+
+/// `SOL-3` is the alert `3` of `Crypto.SOL/USD`.
+fn symbol_of_alert_id(id: &str) -> Option<&str> {
+    id.rsplit_once('-').map(|(symbol, _)| symbol)
+}
+
+/// Id of the alert created by the interaction `interaction_id`, its snowflake in base 36 like
+/// `SOL-7Z9D4Q0RGJ5S`. Unique without reading the ids taken, so never reused.
+fn alert_id(symbol: &str, interaction_id: &str) -> String {
+    let mut n = match interaction_id.parse::<u64>() {
+        Ok(n) => n,
+        Err(_) => return format!("{}-{}", symbol, interaction_id.to_uppercase()),
+    };
+    let mut digits = Vec::new();
+    loop {
+        digits.push(std::char::from_digit((n % 36) as u32, 36).unwrap_or('0'));
+        n /= 36;
+        if n == 0 {
+            break;
+        }
+    }
+    let id: String = digits.iter().rev().collect();
+
+    format!("{}-{}", symbol, id.to_uppercase())
+}
+
+impl Versioned for PriceAlert {
+    const VERSION: u32 = 1;
+}
+
+/// Every alert under `prefix`, each kept at its own key.
+async fn get_alerts(kv: &dyn KeyValueStore, prefix: &str) -> Result<Vec<PriceAlert>, Error> {
+    let mut alerts = Vec::new();
+    for key in kv.list_all(prefix).await? {
+        if let Some(alert) = get_json(kv, &key.name).await? {
+            alerts.push(alert);
+        }
+    }
+
+    Ok(alerts)
+}
+
+async fn put_alert(kv: &dyn KeyValueStore, key: &str, alert: &PriceAlert) -> Result<(), Error> {
+    put_json(kv, key, alert, PutOptions::ttl(ALERT_TTL)).await
+}
+
+/// Alerts of `user_id` across every pair synced by the devnet price sync that checks them.
+pub(crate) async fn list_alerts_by_user(
+    bindings: &Bindings,
+    user_id: &str,
+) -> Result<Vec<PriceAlert>, Error> {
+    let mut alerts = Vec::new();
+    for pair in get_symbols_from_kv(bindings.products(&Cluster::Devnet)).await {
+        let prefix = keys::user_alerts_prefix(&pair, user_id);
+        alerts.extend(get_alerts(bindings.users(), &prefix).await?);
+    }
+
+    Ok(alerts)
+}
+
+/// Store an alert of `user_id` created by the interaction `interaction_id`.
+pub(crate) async fn add_alert(
+    bindings: &Bindings,
+    interaction_id: &str,
+    user_id: &str,
+    channel_id: &str,
+    symbol: &str,
+    direction: AlertDirection,
+    price: f64,
+    rearm: bool,
+) -> Result<PriceAlert, Error> {
+    if !price.is_finite() || price <= 0.0 {
+        return Err(Error::InvalidPayload("price must be positive".to_string()));
+    }
+    if list_alerts_by_user(bindings, user_id).await?.len() >= MAX_ALERTS_PER_USER {
+        return Err(Error::InvalidPayload(format!(
+            "you already have {} alerts, remove one first",
+            MAX_ALERTS_PER_USER
+        )));
+    }
+
+    let symbol = symbol.to_uppercase();
+    let pair = get_pair_by_symbol(&symbol);
+    let kv = bindings.users();
+    let alert = PriceAlert {
+        id: alert_id(&symbol, interaction_id),
+        symbol,
+        direction,
+        price,
+        user_id: user_id.to_string(),
+        channel_id: channel_id.to_string(),
+        rearm,
+        fired: false,
+        fired_at: None,
+    };
+    put_alert(kv, &keys::alert(&pair, user_id, &alert.id), &alert).await?;
+
+    Ok(alert)
+}
+
+/// Remove the alert `id` when it belongs to `user_id`.
+pub(crate) async fn remove_alert(
+    bindings: &Bindings,
+    user_id: &str,
+    id: &str,
+) -> Result<(), Error> {
+    let kv = bindings.users();
+    let id = id.trim().to_uppercase();
+    let symbol = symbol_of_alert_id(&id).ok_or_else(|| Error::NotFound(format!("alert {}", id)))?;
+    let key = keys::alert(&get_pair_by_symbol(symbol), user_id, &id);
+
+    match kv.get_vec(&key).await? {
+        Some(_) => kv.delete(&key).await,
+        None => Err(Error::NotFound(format!("alert {}", id))),
+    }
+}
+
+/// Check the alerts of `pair` against its latest `price` and post the ones that fired.
+///
+/// An alert is only marked fired once its message is posted. Without a `notifier`, or when
+/// posting fails, it stays armed for the next sync.
+pub(crate) async fn check_alerts(
+    kv: &dyn KeyValueStore,
+    notifier: Option<&DiscordClient>,
+    pair: &str,
+    price: f64,
+) -> usize {
+    let keys = kv
+        .list_all(&keys::alerts_prefix(pair))
+        .await
+        .unwrap_or_default();
+
+    let mut fired = 0;
+    for key in keys {
+        // Records that can't be read are left as they are
+        let mut alert: PriceAlert = match get_json(kv, &key.name).await {
+            Ok(Some(alert)) => alert,
+            _ => continue,
+        };
+        let before = alert.clone();
+
+        if alert.check(price) {
+            let content = format!(
+                "🔔 <@{}> `{}` is {} `${:.2}` at `${:.2}`.",
+                alert.user_id, alert.symbol, alert.direction, alert.price, price
+            );
+            let posted = match notifier {
+                Some(client) => client
+                    .create_message(&alert.channel_id, &content, Some(&alert.user_id))
+                    .await
+                    .is_ok(),
+                None => false,
+            };
+            if !posted {
+                continue;
+            }
+            fired += 1;
+        }
+
+        if alert != before {
+            put_alert(kv, &key.name, &alert).await.unwrap_or_default();
+        }
+    }
+
+    fired
+}
+
+#[cfg(test)]
+fn alert(direction: AlertDirection, price: f64, rearm: bool) -> PriceAlert {
+    PriceAlert {
+        id: "SOL-1".to_string(),
+        symbol: "SOL".to_string(),
+        direction,
+        price,
+        user_id: "1".to_string(),
+        channel_id: "2".to_string(),
+        rearm,
+        fired: false,
+        fired_at: None,
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_alert_fires_once() {
+    let mut alert = alert(AlertDirection::Above, 100.0, false);
+
+    assert!(!alert.check(99.0));
+    assert!(alert.check(100.0));
+    assert!(!alert.check(120.0));
+    assert!(!alert.check(90.0));
+    assert!(!alert.check(120.0));
+    assert!(alert.fired);
+}
+
+#[cfg(test)]
+#[test]
+fn test_alert_rearms_after_crossing_back() {
+    let mut alert = alert(AlertDirection::Below, 50.0, true);
+
+    assert!(alert.check(49.0));
+    assert!(!alert.check(48.0));
+    assert!(!alert.check(51.0));
+    assert!(!alert.fired);
+    assert!(alert.check(50.0));
+}
+
+#[cfg(test)]
+#[test]
+fn test_alert_ids() {
+    assert_eq!(alert_id("SOL", "941843501279305768"), "SOL-75LRNNBB4IZS");
+    assert_eq!(alert_id("BTC", "35"), "BTC-Z");
+    assert_eq!(alert_id("BTC", "36"), "BTC-10");
+    assert_eq!(symbol_of_alert_id("SOL-75LRNNBB4IZS"), Some("SOL"));
+    assert_eq!(
+        "Below".parse::<AlertDirection>().unwrap(),
+        AlertDirection::Below
+    );
+    assert!("sideways".parse::<AlertDirection>().is_err());
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_alerts_kept_per_user_and_fired_once_posted() {
+    use crate::testing::MockServer;
+
+    let bindings = Bindings::in_memory();
+    bindings
+        .products(&Cluster::Devnet)
+        .put_text(keys::SYMBOLS, "Crypto.SOL/USD", 60)
+        .await
+        .unwrap();
+    for (interaction_id, user_id) in [("1", "1"), ("2", "2")] {
+        add_alert(
+            &bindings,
+            interaction_id,
+            user_id,
+            "9",
+            "sol",
+            AlertDirection::Above,
+            100.0,
+            false,
+        )
+        .await
+        .unwrap();
+    }
+    let first = list_alerts_by_user(&bindings, "1").await.unwrap();
+    let second = list_alerts_by_user(&bindings, "2").await.unwrap();
+    assert_eq!(
+        (first[0].id.as_str(), second[0].id.as_str()),
+        ("SOL-1", "SOL-2")
+    );
+    assert!(remove_alert(&bindings, "1", "SOL-2").await.is_err());
+
+    // Not posted, both stay armed
+    let kv = bindings.users();
+    let down = MockServer::start(|_request| (500, "{}".to_string())).await;
+    let client = DiscordClient::with_api_url(&down.url(), "token");
+    assert_eq!(check_alerts(kv, None, "Crypto.SOL/USD", 101.0).await, 0);
+    assert_eq!(
+        check_alerts(kv, Some(&client), "Crypto.SOL/USD", 101.0).await,
+        0
+    );
+    assert_eq!(down.requests().len(), 2);
+
+    let up = MockServer::start(|_request| (200, "{}".to_string())).await;
+    let client = DiscordClient::with_api_url(&up.url(), "token");
+    assert_eq!(
+        check_alerts(kv, Some(&client), "Crypto.SOL/USD", 101.0).await,
+        2
+    );
+    assert_eq!(
+        check_alerts(kv, Some(&client), "Crypto.SOL/USD", 102.0).await,
+        0
+    );
+    assert!(list_alerts_by_user(&bindings, "1").await.unwrap()[0].fired);
+
+    remove_alert(&bindings, "1", "SOL-1").await.unwrap();
+    assert!(list_alerts_by_user(&bindings, "1")
+        .await
+        .unwrap()
+        .is_empty());
+}
@@ -0,0 +1,237 @@
+use chrono::Utc;
+
+use crate::{
+    catboard::{
+        consumer::get_pair_by_symbol,
+        history::{read_daily_history, DailyOhlc},
+    },
+    kv::KeyValueStore,
+};
+
+const BACKGROUND: [u8; 4] = [0x2f, 0x31, 0x36, 0xff];
+const GRID: [u8; 4] = [0x40, 0x44, 0x4b, 0xff];
+const LINE: [u8; 4] = [0x84, 0x00, 0xff, 0xff];
+const UP: [u8; 4] = [0x3b, 0xa5, 0x5d, 0xff];
+const DOWN: [u8; 4] = [0xed, 0x42, 0x45, 0xff];
+const PADDING: u32 = 12;
+const WIDTH: u32 = 600;
+const HEIGHT: u32 = 240;
+/// Longest `/chart` range, one KV read per day on a public route.
+const MAX_DAYS: i64 = 90;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum ChartStyle {
+    Line,
+    Candlestick,
+}
+
+impl ChartStyle {
+    pub(crate) fn from_option(value: &str) -> Option<ChartStyle> {
+        match value {
+            "line" => Some(ChartStyle::Line),
+            "candle" => Some(ChartStyle::Candlestick),
+            _ => None,
+        }
+    }
+}
+
+/// Parse a range like `30d` into days.
+pub(crate) fn parse_range(range: &str) -> Option<i64> {
+    let days = range.strip_suffix('d')?.parse::<i64>().ok()?;
+    match days {
+        1..=MAX_DAYS => Some(days),
+        _ => None,
+    }
+}
+
+struct Canvas {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(width: u32, height: u32) -> Self {
+        Canvas {
+            width,
+            height,
+            pixels: BACKGROUND.repeat((width * height) as usize),
+        }
+    }
+
+    fn set(&mut self, x: i64, y: i64, color: [u8; 4]) {
+        if x < 0 || y < 0 || x >= self.width as i64 || y >= self.height as i64 {
+            return;
+        }
+        let offset = ((y as u32 * self.width + x as u32) * 4) as usize;
+        self.pixels[offset..offset + 4].copy_from_slice(&color);
+    }
+
+    fn fill_rect(&mut self, x0: i64, y0: i64, x1: i64, y1: i64, color: [u8; 4]) {
+        for y in y0.min(y1)..=y0.max(y1) {
+            for x in x0.min(x1)..=x0.max(x1) {
+                self.set(x, y, color);
+            }
+        }
+    }
+
+    /// Bresenham line, drawn 2px thick.
+    fn line(&mut self, (x0, y0): (i64, i64), (x1, y1): (i64, i64), color: [u8; 4]) {
+        let (dx, dy) = ((x1 - x0).abs(), -(y1 - y0).abs());
+        let (sx, sy) = (if x0 < x1 { 1 } else { -1 }, if y0 < y1 { 1 } else { -1 });
+        let (mut x, mut y, mut error) = (x0, y0, dx + dy);
+
+        loop {
+            self.fill_rect(x, y, x + 1, y + 1, color);
+            if x == x1 && y == y1 {
+                break;
+            }
+            let e2 = 2 * error;
+            if e2 >= dy {
+                error += dy;
+                x += sx;
+            }
+            if e2 <= dx {
+                error += dx;
+                y += sy;
+            }
+        }
+    }
+
+    fn encode_png(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        {
+            let mut encoder = png::Encoder::new(&mut bytes, self.width, self.height);
+            encoder.set_color(png::ColorType::Rgba);
+            encoder.set_depth(png::BitDepth::Eight);
+            let mut writer = encoder.write_header().unwrap();
+            writer.write_image_data(&self.pixels).unwrap();
+        }
+        bytes
+    }
+}
+
+/// Render daily prices, oldest first, as a PNG image.
+pub(crate) fn render_chart(
+    history: &[DailyOhlc],
+    style: ChartStyle,
+    width: u32,
+    height: u32,
+) -> Vec<u8> {
+    let mut canvas = Canvas::new(width, height);
+
+    // Horizontal grid at quarters
+    for i in 0..=4 {
+        let y = (PADDING + (height - 2 * PADDING) * i / 4) as i64;
+        canvas.fill_rect(PADDING as i64, y, (width - PADDING) as i64, y, GRID);
+    }
+
+    if history.is_empty() {
+        return canvas.encode_png();
+    }
+
+    let (low, high) = match style {
+        ChartStyle::Line => history.iter().fold((f64::MAX, f64::MIN), |(l, h), ohlc| {
+            (l.min(ohlc.close), h.max(ohlc.close))
+        }),
+        ChartStyle::Candlestick => history.iter().fold((f64::MAX, f64::MIN), |(l, h), ohlc| {
+            (l.min(ohlc.low), h.max(ohlc.high))
+        }),
+    };
+    let range = if high > low { high - low } else { 1.0 };
+
+    let plot_width = (width - 2 * PADDING) as f64;
+    let plot_height = (height - 2 * PADDING) as f64;
+    let step = plot_width / history.len() as f64;
+    let x_of = |i: usize| (PADDING as f64 + step * (i as f64 + 0.5)) as i64;
+    let y_of = |price: f64| (PADDING as f64 + (high - price) / range * plot_height) as i64;
+
+    match style {
+        ChartStyle::Line => {
+            let points: Vec<(i64, i64)> = history
+                .iter()
+                .enumerate()
+                .map(|(i, ohlc)| (x_of(i), y_of(ohlc.close)))
+                .collect();
+            if points.len() == 1 {
+                canvas.fill_rect(
+                    points[0].0 - 2,
+                    points[0].1 - 2,
+                    points[0].0 + 2,
+                    points[0].1 + 2,
+                    LINE,
+                );
+            }
+            for pair in points.windows(2) {
+                canvas.line(pair[0], pair[1], LINE);
+            }
+        }
+        ChartStyle::Candlestick => {
+            let half_body = ((step * 0.35) as i64).max(1);
+            for (i, ohlc) in history.iter().enumerate() {
+                let color = if ohlc.close >= ohlc.open { UP } else { DOWN };
+                let x = x_of(i);
+                canvas.fill_rect(x, y_of(ohlc.high), x, y_of(ohlc.low), color);
+                canvas.fill_rect(
+                    x - half_body,
+                    y_of(ohlc.open),
+                    x + half_body,
+                    y_of(ohlc.close),
+                    color,
+                );
+            }
+        }
+    }
+
+    canvas.encode_png()
+}
+
+/// Chart of the daily history of `symbol`, `None` when nothing was recorded in range.
+pub(crate) async fn render_symbol_chart(
+    kv: &dyn KeyValueStore,
+    symbol: &str,
+    days: i64,
+    style: ChartStyle,
+) -> Option<Vec<u8>> {
+    let today = Utc::now().naive_utc().date();
+    let history = read_daily_history(kv, &get_pair_by_symbol(symbol), today, days).await;
+    if history.is_empty() {
+        return None;
+    }
+
+    let history: Vec<DailyOhlc> = history.into_iter().map(|(_, ohlc)| ohlc).collect();
+    Some(render_chart(&history, style, WIDTH, HEIGHT))
+}
+
+#[cfg(test)]
+#[test]
+fn test_parse_range() {
+    assert_eq!(parse_range("7d"), Some(7));
+    assert_eq!(parse_range("90d"), Some(90));
+    assert_eq!(parse_range("365d"), None);
+    assert_eq!(parse_range("0d"), None);
+    assert_eq!(parse_range("1y"), None);
+}
+
+#[cfg(test)]
+#[test]
+fn test_render_chart_png() {
+    let history: Vec<DailyOhlc> = [100.0, 110.0, 95.0, 120.0]
+        .iter()
+        .map(|&price| {
+            let mut ohlc = DailyOhlc::new(price);
+            ohlc.update(price * 1.05);
+            ohlc.update(price * 0.9);
+            ohlc
+        })
+        .collect();
+
+    for style in [ChartStyle::Line, ChartStyle::Candlestick] {
+        let bytes = render_chart(&history, style, 320, 160);
+        let decoder = png::Decoder::new(bytes.as_slice());
+        let reader = decoder.read_info().unwrap();
+
+        assert_eq!(reader.info().width, 320);
+        assert_eq!(reader.info().height, 160);
+    }
+}
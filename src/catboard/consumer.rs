@@ -1,10 +1,6 @@
-use crate::{
-    cloudflare::worker::WorkersKv,
-    pyth::adaptor::fetch_pyth_price_by_pubkey,
-    solana::{pubkey::Pubkey, web3::Cluster},
-};
+use crate::kv::{keys, KeyValueStore};
+use pyth_client::PriceConf;
 use rusty_money::{define_currency_set, Money, Round};
-use std::str::FromStr;
 
 // TODO: cached this
 define_currency_set!(
@@ -40,7 +36,7 @@ define_currency_set!(
 );
 
 #[allow(dead_code)]
-async fn get_kv_text(kv: &WorkersKv, key: &str) -> String {
+async fn get_kv_text(kv: &dyn KeyValueStore, key: &str) -> String {
     kv.get_text(&key)
         .await
         .unwrap_or_default()
@@ -57,26 +53,37 @@ pub(crate) fn get_supported_symbols() -> Vec<&'static str> {
     symbols
 }
 
-pub(crate) async fn get_price_account_from_kv_by_symbol(kv: &WorkersKv, symbol: &str) -> String {
-    let symbol = format!("{}", symbol.to_uppercase());
-    let pair = format!("{}/USD", symbol);
-    let key = format!("Crypto.{}:price_account", pair);
+pub(crate) async fn get_symbols_from_kv(kv: &dyn KeyValueStore) -> Vec<String> {
+    get_kv_text(kv, keys::SYMBOLS)
+        .await
+        .split(',')
+        .filter(|symbol| !symbol.is_empty())
+        .map(|symbol| symbol.to_string())
+        .collect()
+}
 
-    // Get price_account from kv
-    let price_account = get_kv_text(&kv, &key).await;
+pub(crate) async fn get_last_synced_at_from_kv(kv: &dyn KeyValueStore) -> Option<String> {
+    Some(get_kv_text(kv, keys::LAST_SYNCED_AT).await).filter(|text| !text.is_empty())
+}
 
-    price_account
+/// Pyth product name of a symbol, e.g. `SOL` is `Crypto.SOL/USD`.
+pub(crate) fn get_pair_by_symbol(symbol: &str) -> String {
+    format!("Crypto.{}/USD", symbol.to_uppercase())
 }
 
-pub(crate) async fn get_formatted_price_from_pyth(
-    cluster: &Cluster,
+pub(crate) async fn get_price_account_from_kv_by_symbol(
+    kv: &dyn KeyValueStore,
     symbol: &str,
-    price_account: &str,
 ) -> String {
-    // Fetch price from pyth
-    let pubkey = Pubkey::from_str(price_account).unwrap();
-    let price_conf = fetch_pyth_price_by_pubkey(cluster, &pubkey).await.unwrap();
+    let key = keys::price_account(&get_pair_by_symbol(symbol));
+
+    // Get price_account from kv
+    let price_account = get_kv_text(kv, &key).await;
+
+    price_account
+}
 
+pub(crate) fn format_price(symbol: &str, price_conf: &PriceConf) -> String {
     let price = Money::from_minor(price_conf.price, crypto::SOL);
     let price_round_up = price.round(2, Round::HalfUp);
     let conf = (price_conf.conf as f64) / (crypto::SOL.minor_units as f64);
@@ -102,7 +109,7 @@ pub(crate) async fn get_formatted_price_from_pyth(
 //     unsafe { STATE }
 // }
 
-// pub(crate) async fn feed(kv: &WorkersKv) -> Result<Response, Error> {
+// pub(crate) async fn feed(kv: &dyn KeyValueStore) -> Result<Response, Error> {
 //     let result = fetch_pyth_prices_and_record(kv).await;
 //     Ok(())
 // }
@@ -0,0 +1,207 @@
+use rust_decimal::{prelude::FromPrimitive, Decimal};
+use rusty_money::{
+    iso, FormattableCurrency, Formatter, LocalFormat, Money, Params, Position, Round,
+};
+use std::str::FromStr;
+
+use crate::{
+    catboard::{
+        consumer::{crypto, get_pair_by_symbol},
+        watchlist::to_usd,
+    },
+    endpoints::Endpoints,
+    error::Error,
+    jupiter::token::find_token_by_symbol,
+    kv::{bindings::Bindings, keys},
+    pyth::adaptor::fetch_pyth_prices_by_pubkeys,
+    solana::{pubkey::Pubkey, web3::Cluster},
+};
+
+/// Exponent of currencies we have a price of but no definition for.
+const DEFAULT_EXPONENT: u32 = 8;
+
+/// Price of one unit in USD with its confidence interval.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct UsdRate {
+    pub(crate) price: f64,
+    pub(crate) conf: f64,
+}
+
+impl UsdRate {
+    const USD: UsdRate = UsdRate {
+        price: 1.0,
+        conf: 0.0,
+    };
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Conversion {
+    pub(crate) value: f64,
+    pub(crate) low: f64,
+    /// `None` when the confidence of `to` reaches its price, nothing bounds it.
+    pub(crate) high: Option<f64>,
+}
+
+fn is_fiat(code: &str) -> bool {
+    crypto::find(code).is_none() && iso::find(code).is_some()
+}
+
+/// Pyth product quoting `code` in USD, e.g. `SOL` is `Crypto.SOL/USD` and `EUR` is `FX.EUR/USD`.
+fn get_pair_by_code(code: &str) -> String {
+    match is_fiat(code) {
+        true => format!("FX.{}/USD", code),
+        false => get_pair_by_symbol(code),
+    }
+}
+
+/// Convert `amount` of `from` through their USD rates, widening the range by both confidences.
+pub(crate) fn convert(amount: f64, from: UsdRate, to: UsdRate) -> Conversion {
+    let value = amount * from.price / to.price;
+    let low = amount * (from.price - from.conf) / (to.price + to.conf);
+    let high = match to.price - to.conf {
+        denominator if denominator > 0.0 => Some(amount * (from.price + from.conf) / denominator),
+        _ => None,
+    };
+
+    Conversion {
+        value,
+        low: low.max(0.0),
+        high,
+    }
+}
+
+/// Same as `Money` shows itself, with `exponent` digits instead of the currency's.
+fn format_money<T: FormattableCurrency>(value: Decimal, currency: &T, exponent: u32) -> String {
+    let money = Money::from_decimal(value, currency).round(exponent, Round::HalfUp);
+    let format = LocalFormat::from_locale(currency.locale());
+    let positions = match currency.symbol_first() {
+        true => vec![Position::Sign, Position::Symbol, Position::Amount],
+        false => vec![Position::Sign, Position::Amount, Position::Symbol],
+    };
+    let params = Params {
+        digit_separator: format.digit_separator,
+        exponent_separator: format.exponent_separator,
+        separator_pattern: format.digit_separator_pattern(),
+        positions,
+        rounding: Some(exponent),
+        symbol: Some(currency.symbol()),
+        code: Some(currency.code()),
+    };
+
+    format!(
+        "{} {}",
+        Formatter::money(&money, params).trim(),
+        currency.code()
+    )
+}
+
+/// Format `value` rounded to the exponent of `code`, e.g. `$1,234.57 USD` or `0.12345679 BTC`.
+/// Tokens we know the mint of are rounded to its decimals, 9 for SOL.
+pub(crate) fn format_amount(value: f64, code: &str) -> String {
+    let value = match Decimal::from_f64(value) {
+        Some(value) => value,
+        None => return format!("{} {}", value, code),
+    };
+
+    if let Some(currency) = crypto::find(code) {
+        let exponent = match find_token_by_symbol(code) {
+            Some(token) => token.decimals,
+            None => currency.exponent(),
+        };
+        format_money(value, currency, exponent)
+    } else if let Some(currency) = iso::find(code) {
+        format_money(value, currency, currency.exponent())
+    } else {
+        format!("{} {}", value.round_dp(DEFAULT_EXPONENT).normalize(), code)
+    }
+}
+
+/// USD rates of `codes` from their synced Pyth price accounts, fetched in one batch.
+pub(crate) async fn fetch_usd_rates(
+    bindings: &Bindings,
+    endpoints: &Endpoints,
+    cluster: &Cluster,
+    codes: &[&str],
+) -> Result<Vec<UsdRate>, Error> {
+    let kv = bindings.products(cluster);
+    let mut rates = vec![UsdRate::USD; codes.len()];
+    let mut px_pkeys = Vec::new();
+    let mut indexes = Vec::new();
+
+    for (index, code) in codes.iter().enumerate() {
+        if *code == "USD" {
+            continue;
+        }
+
+        // Price account from last product sync
+        let key = keys::price_account(&get_pair_by_code(code));
+        let price_account = kv.get_text(&key).await.unwrap_or_default();
+        match price_account.as_deref().map(Pubkey::from_str) {
+            Some(Ok(pubkey)) => {
+                px_pkeys.push(pubkey);
+                indexes.push(index);
+            }
+            _ => return Err(Error::NotFound(format!("price of {}", code))),
+        }
+    }
+
+    let price_confs = fetch_pyth_prices_by_pubkeys(&endpoints.rpc(cluster), &px_pkeys).await?;
+    for (index, price_conf) in indexes.into_iter().zip(price_confs) {
+        let (price, conf) = price_conf
+            .as_ref()
+            .map(to_usd)
+            .ok_or_else(|| Error::NotFound(format!("current price of {}", codes[index])))?;
+        rates[index] = UsdRate { price, conf };
+    }
+
+    Ok(rates)
+}
+
+#[cfg(test)]
+#[test]
+fn test_convert_through_usd() {
+    let sol = UsdRate {
+        price: 100.0,
+        conf: 1.0,
+    };
+    let eth = UsdRate {
+        price: 2_000.0,
+        conf: 10.0,
+    };
+
+    let to_usd = convert(3.5, sol, UsdRate::USD);
+    assert_eq!(to_usd.value, 350.0);
+    assert_eq!(to_usd.low, 346.5);
+    assert_eq!(to_usd.high, Some(353.5));
+
+    let from_usd = convert(100.0, UsdRate::USD, sol);
+    assert_eq!(from_usd.value, 1.0);
+    assert!(from_usd.low < 1.0 && from_usd.high.unwrap() > 1.0);
+
+    let cross = convert(20.0, sol, eth);
+    assert_eq!(cross.value, 1.0);
+    assert!((cross.low - 20.0 * 99.0 / 2_010.0).abs() < 1e-12);
+    assert!((cross.high.unwrap() - 20.0 * 101.0 / 1_990.0).abs() < 1e-12);
+
+    let unbounded = UsdRate {
+        price: 1.0,
+        conf: 1.0,
+    };
+    let wide = convert(1.0, sol, unbounded);
+    assert_eq!(wide.value, 100.0);
+    assert_eq!(wide.high, None);
+}
+
+#[cfg(test)]
+#[test]
+fn test_format_amount_rounds_per_exponent() {
+    assert_eq!(format_amount(1234.5678, "USD"), "$1,234.57 USD");
+    assert_eq!(format_amount(1234.5, "JPY"), "¥1,235 JPY");
+    assert_eq!(format_amount(3.5, "SOL"), "3.5 SOL");
+    assert_eq!(format_amount(0.123456789, "SOL"), "0.123456789 SOL");
+    assert_eq!(format_amount(0.1234567894, "SOL"), "0.123456789 SOL");
+    assert_eq!(format_amount(0.123456789, "BTC"), "0.12345679 BTC");
+    assert_eq!(format_amount(1.5, "DOGE"), "1.5 DOGE");
+    assert_eq!(get_pair_by_code("EUR"), "FX.EUR/USD");
+    assert_eq!(get_pair_by_code("SOL"), "Crypto.SOL/USD");
+}
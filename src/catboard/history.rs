@@ -0,0 +1,105 @@
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::kv::{
+    json::{get_json, put_json, Versioned},
+    keys, KeyValueStore, PutOptions,
+};
+
+/// One day of prices in USD, kept at `Crypto.SOL/USD:2022-01-01`.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub(crate) struct DailyOhlc {
+    pub(crate) open: f64,
+    pub(crate) high: f64,
+    pub(crate) low: f64,
+    pub(crate) close: f64,
+}
+
+impl DailyOhlc {
+    pub(crate) fn new(price: f64) -> Self {
+        DailyOhlc {
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+        }
+    }
+
+    pub(crate) fn update(&mut self, price: f64) {
+        self.high = self.high.max(price);
+        self.low = self.low.min(price);
+        self.close = price;
+    }
+}
+
+impl Versioned for DailyOhlc {
+    const VERSION: u32 = 1;
+}
+
+async fn get_daily_ohlc(kv: &dyn KeyValueStore, pair: &str, date: NaiveDate) -> Option<DailyOhlc> {
+    get_json(kv, &keys::daily_price(pair, date))
+        .await
+        .unwrap_or_default()
+}
+
+/// Merge `price` into the record of `date`, starting a new day when there is none.
+pub(crate) async fn record_daily_price(
+    kv: &dyn KeyValueStore,
+    pair: &str,
+    date: NaiveDate,
+    price: f64,
+) {
+    let ohlc = match get_daily_ohlc(kv, pair, date).await {
+        Some(mut ohlc) => {
+            ohlc.update(price);
+            ohlc
+        }
+        None => DailyOhlc::new(price),
+    };
+
+    put_json(
+        kv,
+        &keys::daily_price(pair, date),
+        &ohlc,
+        PutOptions::ttl(60 * 60 * 24 * 365),
+    )
+    .await
+    .unwrap_or_default();
+}
+
+/// Daily records of the `days` days up to `end`, oldest first, skipping days without a record.
+pub(crate) async fn read_daily_history(
+    kv: &dyn KeyValueStore,
+    pair: &str,
+    end: NaiveDate,
+    days: i64,
+) -> Vec<(NaiveDate, DailyOhlc)> {
+    let mut history = Vec::new();
+    for offset in (0..days).rev() {
+        let date = end - Duration::days(offset);
+        if let Some(ohlc) = get_daily_ohlc(kv, pair, date).await {
+            history.push((date, ohlc));
+        }
+    }
+
+    history
+}
+
+#[cfg(test)]
+#[test]
+fn test_daily_ohlc_update() {
+    let mut ohlc = DailyOhlc::new(100.0);
+    ohlc.update(120.0);
+    ohlc.update(90.0);
+    ohlc.update(110.0);
+
+    assert_eq!(
+        ohlc,
+        DailyOhlc {
+            open: 100.0,
+            high: 120.0,
+            low: 90.0,
+            close: 110.0,
+        }
+    );
+}
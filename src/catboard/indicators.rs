@@ -0,0 +1,610 @@
+use chrono::{Datelike, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::{
+    catboard::history::{read_daily_history, DailyOhlc},
+    kv::{
+        json::{get_json, put_json, Versioned},
+        keys, KeyValueStore, PutOptions,
+    },
+};
+
+/// Days of history read to have every indicator warmed up.
+const HISTORY_DAYS: i64 = 90;
+
+/// The summary and its state are dropped when the price sync stops updating them, the state is
+/// then seeded from the history again.
+const SUMMARY_TTL: u64 = 60 * 60 * 24;
+
+/// Windows in days, every indicator is computed on daily closes.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct IndicatorConfig {
+    pub(crate) sma_windows: Vec<usize>,
+    pub(crate) ema_windows: Vec<usize>,
+    pub(crate) rsi_period: usize,
+    pub(crate) bollinger_window: usize,
+    pub(crate) bollinger_k: f64,
+}
+
+impl Default for IndicatorConfig {
+    fn default() -> Self {
+        IndicatorConfig {
+            sma_windows: vec![7, 30],
+            ema_windows: vec![12, 26],
+            rsi_period: 14,
+            bollinger_window: 20,
+            bollinger_k: 2.0,
+        }
+    }
+}
+
+/// Change in percent from `base` to `last`.
+pub(crate) fn change(base: f64, last: f64) -> Option<f64> {
+    if base == 0.0 {
+        return None;
+    }
+
+    Some((last - base) / base * 100.0)
+}
+
+/// Change in percent from the close `days` before the latest record to the latest close, `None`
+/// when that day has no record.
+fn change_over(history: &[(NaiveDate, DailyOhlc)], days: i64) -> Option<f64> {
+    let (end, last) = history.last()?;
+    let base_date = *end - Duration::days(days);
+    let (_, base) = history.iter().find(|(date, _)| *date == base_date)?;
+
+    change(base.close, last.close)
+}
+
+/// Closes of the days up to the latest record without a day missing, oldest first.
+fn consecutive_closes(history: &[(NaiveDate, DailyOhlc)]) -> Vec<f64> {
+    let gap = history
+        .windows(2)
+        .rposition(|pair| pair[1].0 - pair[0].0 != Duration::days(1))
+        .map(|index| index + 1)
+        .unwrap_or(0);
+
+    history[gap..].iter().map(|(_, ohlc)| ohlc.close).collect()
+}
+
+/// Simple moving average of the last `window` values.
+pub(crate) fn sma(values: &[f64], window: usize) -> Option<f64> {
+    if window == 0 || values.len() < window {
+        return None;
+    }
+
+    Some(values[values.len() - window..].iter().sum::<f64>() / window as f64)
+}
+
+/// Exponential moving average seeded with the SMA of the first `window` values.
+pub(crate) fn ema(values: &[f64], window: usize) -> Option<f64> {
+    let seed = sma(&values[..window.min(values.len())], window)?;
+    let alpha = 2.0 / (window as f64 + 1.0);
+
+    Some(
+        values[window..]
+            .iter()
+            .fold(seed, |ema, value| alpha * value + (1.0 - alpha) * ema),
+    )
+}
+
+/// Wilder's relative strength index, 0 to 100.
+pub(crate) fn rsi(values: &[f64], period: usize) -> Option<f64> {
+    if period == 0 || values.len() <= period {
+        return None;
+    }
+
+    let deltas: Vec<f64> = values.windows(2).map(|pair| pair[1] - pair[0]).collect();
+    let gain = |delta: &f64| delta.max(0.0);
+    let loss = |delta: &f64| (-delta).max(0.0);
+
+    let mut avg_gain = deltas[..period].iter().map(gain).sum::<f64>() / period as f64;
+    let mut avg_loss = deltas[..period].iter().map(loss).sum::<f64>() / period as f64;
+    for delta in deltas[period..].iter() {
+        avg_gain = (avg_gain * (period - 1) as f64 + gain(delta)) / period as f64;
+        avg_loss = (avg_loss * (period - 1) as f64 + loss(delta)) / period as f64;
+    }
+
+    if avg_loss == 0.0 {
+        return Some(if avg_gain == 0.0 { 50.0 } else { 100.0 });
+    }
+    Some(100.0 - 100.0 / (1.0 + avg_gain / avg_loss))
+}
+
+/// Position of the latest value within the Bollinger bands, 0 at the lower band and 1 at the upper (%B).
+pub(crate) fn bollinger_position(values: &[f64], window: usize, k: f64) -> Option<f64> {
+    let mean = sma(values, window)?;
+    let recent = &values[values.len() - window..];
+    let variance = recent.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / window as f64;
+    let width = 2.0 * k * variance.sqrt();
+    if width == 0.0 {
+        return Some(0.5);
+    }
+
+    Some((values[values.len() - 1] - (mean - k * variance.sqrt())) / width)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Indicators {
+    pub(crate) price: f64,
+    pub(crate) change_24h: Option<f64>,
+    pub(crate) change_7d: Option<f64>,
+    pub(crate) change_30d: Option<f64>,
+    pub(crate) sma: Vec<(usize, Option<f64>)>,
+    pub(crate) ema: Vec<(usize, Option<f64>)>,
+    pub(crate) rsi_period: usize,
+    pub(crate) rsi: Option<f64>,
+    pub(crate) bollinger: Option<f64>,
+}
+
+impl Indicators {
+    /// Indicators of daily records, oldest first, `None` without any record.
+    ///
+    /// Changes compare with the record of that many days before the latest one. Windowed
+    /// indicators only use the days since the last missing one, so a gap leaves them `None`
+    /// until the window fills again.
+    pub(crate) fn from_history(
+        history: &[(NaiveDate, DailyOhlc)],
+        config: &IndicatorConfig,
+    ) -> Option<Indicators> {
+        let closes = consecutive_closes(history);
+
+        Some(Indicators {
+            price: history.last()?.1.close,
+            change_24h: change_over(history, 1),
+            change_7d: change_over(history, 7),
+            change_30d: change_over(history, 30),
+            sma: config
+                .sma_windows
+                .iter()
+                .map(|&window| (window, sma(&closes, window)))
+                .collect(),
+            ema: config
+                .ema_windows
+                .iter()
+                .map(|&window| (window, ema(&closes, window)))
+                .collect(),
+            rsi_period: config.rsi_period,
+            rsi: rsi(&closes, config.rsi_period),
+            bollinger: bollinger_position(&closes, config.bollinger_window, config.bollinger_k),
+        })
+    }
+}
+
+/// One line for `/price`, e.g. `24h +1.20% 📈 · 7d -3.40% 📉 · RSI 55`.
+fn summary_line(
+    change_24h: Option<f64>,
+    change_7d: Option<f64>,
+    rsi: Option<f64>,
+) -> Option<String> {
+    let mut parts = Vec::new();
+    for (label, change) in [("24h", change_24h), ("7d", change_7d)] {
+        if let Some(change) = change {
+            parts.push(format!("{} `{:+.2}%` {}", label, change, trend(change)));
+        }
+    }
+    if let Some(rsi) = rsi {
+        parts.push(format!("RSI `{:.0}`", rsi));
+    }
+
+    match parts.is_empty() {
+        true => None,
+        false => Some(parts.join(" · ")),
+    }
+}
+
+/// Wilder's averages of `rsi`, one delta at a time.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+struct RsiState {
+    deltas: usize,
+    /// Sums until `period` deltas are seen, averages from then on.
+    gain: f64,
+    loss: f64,
+}
+
+impl RsiState {
+    fn push(self, delta: f64, period: usize) -> RsiState {
+        if period == 0 {
+            return self;
+        }
+
+        let (gain, loss) = (delta.max(0.0), (-delta).max(0.0));
+        let deltas = self.deltas + 1;
+        let period_f = period as f64;
+        match deltas.cmp(&period) {
+            std::cmp::Ordering::Less => RsiState {
+                deltas,
+                gain: self.gain + gain,
+                loss: self.loss + loss,
+            },
+            std::cmp::Ordering::Equal => RsiState {
+                deltas,
+                gain: (self.gain + gain) / period_f,
+                loss: (self.loss + loss) / period_f,
+            },
+            std::cmp::Ordering::Greater => RsiState {
+                deltas,
+                gain: (self.gain * (period_f - 1.0) + gain) / period_f,
+                loss: (self.loss * (period_f - 1.0) + loss) / period_f,
+            },
+        }
+    }
+
+    fn value(&self, period: usize) -> Option<f64> {
+        if period == 0 || self.deltas < period {
+            return None;
+        }
+
+        if self.loss == 0.0 {
+            return Some(if self.gain == 0.0 { 50.0 } else { 100.0 });
+        }
+        Some(100.0 - 100.0 / (1.0 + self.gain / self.loss))
+    }
+}
+
+/// What the `/price` summary needs of the history, kept at `Crypto.SOL/USD:indicator_state` and
+/// advanced by the price sync with each close instead of reading the history again.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub(crate) struct SummaryState {
+    /// Closes of the latest day and the 7 before it by `num_days_from_ce`, oldest first.
+    closes: Vec<(i32, f64)>,
+    /// RSI averages of the consecutive days up to the one before the latest, the latest close
+    /// still changes during its day.
+    rsi: RsiState,
+}
+
+impl Versioned for SummaryState {
+    const VERSION: u32 = 1;
+}
+
+impl SummaryState {
+    /// State of daily records, oldest first, as `Indicators::from_history` reads them.
+    pub(crate) fn from_history(history: &[(NaiveDate, DailyOhlc)], rsi_period: usize) -> Self {
+        let mut state = SummaryState::default();
+        for (date, ohlc) in history.iter() {
+            state.update(*date, ohlc.close, rsi_period);
+        }
+
+        state
+    }
+
+    /// Change between the latest close and the one before it, when that was the day before.
+    fn latest_delta(&self) -> Option<f64> {
+        match self.closes.as_slice() {
+            [.., (previous, before), (latest, last)] if latest - previous == 1 => {
+                Some(last - before)
+            }
+            _ => None,
+        }
+    }
+
+    /// Take `close` as the close of `date`, replacing the latest one on the same day. A missing
+    /// day restarts the RSI like a gap in the history does, an earlier date is ignored.
+    pub(crate) fn update(&mut self, date: NaiveDate, close: f64, rsi_period: usize) {
+        let day = date.num_days_from_ce();
+        match self.closes.last().map(|(latest, _)| *latest) {
+            Some(latest) if day < latest => return,
+            Some(latest) if day == latest => {
+                self.closes.pop();
+            }
+            Some(latest) if day - latest == 1 => {
+                if let Some(delta) = self.latest_delta() {
+                    self.rsi = self.rsi.push(delta, rsi_period);
+                }
+            }
+            Some(_) => self.rsi = RsiState::default(),
+            None => {}
+        }
+
+        self.closes.push((day, close));
+        self.closes.retain(|(kept, _)| day - kept <= 7);
+    }
+
+    /// Line of `/price` with the changes and RSI of `Indicators::from_history`, over the history
+    /// the state was advanced with.
+    pub(crate) fn summary(&self, rsi_period: usize) -> Option<String> {
+        let (end, last) = *self.closes.last()?;
+        let change_over = |days: i32| {
+            let (_, base) = self.closes.iter().find(|(day, _)| *day == end - days)?;
+            change(*base, last)
+        };
+        let rsi = self
+            .latest_delta()
+            .and_then(|delta| self.rsi.push(delta, rsi_period).value(rsi_period));
+
+        summary_line(change_over(1), change_over(7), rsi)
+    }
+}
+
+fn trend(change: f64) -> &'static str {
+    match change >= 0.0 {
+        true => "📈",
+        false => "📉",
+    }
+}
+
+fn or_na(value: Option<f64>, format: impl Fn(f64) -> String) -> String {
+    value.map(format).unwrap_or_else(|| "n/a".to_string())
+}
+
+fn rsi_label(rsi: f64) -> &'static str {
+    match rsi {
+        rsi if rsi >= 70.0 => " overbought",
+        rsi if rsi <= 30.0 => " oversold",
+        _ => "",
+    }
+}
+
+impl fmt::Display for Indicators {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let percent = |change: f64| format!("{:+.2}%", change);
+        let usd = |value: f64| format!("${:.2}", value);
+
+        writeln!(f, "Price `${:.2}`", self.price)?;
+        writeln!(
+            f,
+            "Change 24h `{}` · 7d `{}` · 30d `{}`",
+            or_na(self.change_24h, percent),
+            or_na(self.change_7d, percent),
+            or_na(self.change_30d, percent)
+        )?;
+        for (window, value) in self.sma.iter() {
+            writeln!(f, "SMA({}) `{}`", window, or_na(*value, usd))?;
+        }
+        for (window, value) in self.ema.iter() {
+            writeln!(f, "EMA({}) `{}`", window, or_na(*value, usd))?;
+        }
+        writeln!(
+            f,
+            "RSI({}) `{}`{}",
+            self.rsi_period,
+            or_na(self.rsi, |rsi| format!("{:.1}", rsi)),
+            self.rsi.map(rsi_label).unwrap_or_default()
+        )?;
+        write!(
+            f,
+            "Bollinger `{}`",
+            or_na(self.bollinger, |position| format!(
+                "{:.0}%",
+                position * 100.0
+            ))
+        )
+    }
+}
+
+/// Indicators of `pair` from its recorded daily history.
+pub(crate) async fn get_indicators(
+    kv: &dyn KeyValueStore,
+    pair: &str,
+    config: &IndicatorConfig,
+) -> Option<Indicators> {
+    let today = Utc::now().naive_utc().date();
+    let history = read_daily_history(kv, pair, today, HISTORY_DAYS).await;
+
+    Indicators::from_history(&history, config)
+}
+
+/// Keep the `/price` summary of `pair` at `Crypto.SOL/USD:indicators` with the `close` of `date`,
+/// called by the price sync so `/price` reads one key instead of the whole history.
+///
+/// The sync reads and writes one state key, the history is only read to seed a missing state.
+pub(crate) async fn record_indicator_summary(
+    kv: &dyn KeyValueStore,
+    pair: &str,
+    date: NaiveDate,
+    close: f64,
+) {
+    let rsi_period = IndicatorConfig::default().rsi_period;
+    let key = keys::indicator_state(pair);
+    let mut state = match get_json::<SummaryState>(kv, &key).await.unwrap_or_default() {
+        Some(state) => state,
+        None => {
+            let history = read_daily_history(kv, pair, date, HISTORY_DAYS).await;
+            SummaryState::from_history(&history, rsi_period)
+        }
+    };
+    state.update(date, close, rsi_period);
+
+    put_json(kv, &key, &state, PutOptions::ttl(SUMMARY_TTL))
+        .await
+        .unwrap_or_default();
+    if let Some(summary) = state.summary(rsi_period) {
+        kv.put_text(&keys::indicator_summary(pair), &summary, SUMMARY_TTL)
+            .await
+            .unwrap_or_default();
+    }
+}
+
+/// Summary of `pair` kept by `record_indicator_summary`.
+pub(crate) async fn get_indicator_summary(kv: &dyn KeyValueStore, pair: &str) -> Option<String> {
+    kv.get_text(&keys::indicator_summary(pair))
+        .await
+        .unwrap_or_default()
+}
+
+/// Consecutive daily records of `closes` from 2022-01-01, for tests.
+#[cfg(test)]
+fn daily(closes: &[f64]) -> Vec<(NaiveDate, DailyOhlc)> {
+    let start = NaiveDate::from_ymd(2022, 1, 1);
+
+    closes
+        .iter()
+        .enumerate()
+        .map(|(day, close)| (start + Duration::days(day as i64), DailyOhlc::new(*close)))
+        .collect()
+}
+
+/// The summary line as computed from the whole history.
+#[cfg(test)]
+fn summary_of(indicators: &Indicators) -> Option<String> {
+    summary_line(indicators.change_24h, indicators.change_7d, indicators.rsi)
+}
+
+#[cfg(test)]
+fn assert_close(actual: Option<f64>, expected: f64) {
+    let actual = actual.unwrap();
+    assert!(
+        (actual - expected).abs() < 1e-9,
+        "{} != {}",
+        actual,
+        expected
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_change_and_moving_averages() {
+    let closes = [10.0, 11.0, 12.0, 13.0, 14.0];
+    let history = daily(&closes);
+
+    assert_close(change_over(&history, 1), (14.0 - 13.0) / 13.0 * 100.0);
+    assert_close(change_over(&history, 4), 40.0);
+    assert_eq!(change_over(&history, 5), None);
+    assert_eq!(change(0.0, 1.0), None);
+    assert_close(sma(&closes, 3), 13.0);
+    assert_eq!(sma(&closes, 6), None);
+    // seed 11 from [10, 11, 12], alpha 0.5: 12, then 13
+    assert_close(ema(&closes, 3), 13.0);
+    assert_eq!(ema(&closes, 6), None);
+}
+
+#[cfg(test)]
+#[test]
+fn test_rsi() {
+    let rising: Vec<f64> = (0..20).map(|i| i as f64).collect();
+    assert_close(rsi(&rising, 14), 100.0);
+    assert_eq!(rsi(&rising[..14], 14), None);
+
+    // Alternating +2/-1: average gain 1, average loss 0.5 over 14 deltas
+    let mut zigzag = vec![100.0];
+    for i in 0..14 {
+        let last = zigzag[i];
+        zigzag.push(if i % 2 == 0 { last + 2.0 } else { last - 1.0 });
+    }
+    assert_close(rsi(&zigzag, 14), 100.0 - 100.0 / (1.0 + 2.0));
+}
+
+#[cfg(test)]
+#[test]
+fn test_bollinger_position() {
+    // mean 2, population std dev sqrt(2/3)
+    let values = [1.0, 2.0, 3.0];
+    let std_dev = (2.0f64 / 3.0).sqrt();
+    assert_close(
+        bollinger_position(&values, 3, 2.0),
+        (3.0 - (2.0 - 2.0 * std_dev)) / (4.0 * std_dev),
+    );
+    assert_close(bollinger_position(&[5.0; 20], 20, 2.0), 0.5);
+}
+
+#[cfg(test)]
+#[test]
+fn test_indicators_text() {
+    let closes: Vec<f64> = (1..=40).map(|i| i as f64).collect();
+    let indicators =
+        Indicators::from_history(&daily(&closes), &IndicatorConfig::default()).unwrap();
+
+    assert_eq!(
+        SummaryState::from_history(&daily(&closes), 14)
+            .summary(14)
+            .unwrap(),
+        "24h `+2.56%` 📈 · 7d `+21.21%` 📈 · RSI `100`"
+    );
+    let text = indicators.to_string();
+    assert!(text.contains("Change 24h `+2.56%` · 7d `+21.21%` · 30d `+300.00%`"));
+    assert!(text.contains("SMA(7) `$37.00`"));
+    assert!(text.contains("RSI(14) `100.0` overbought"));
+    assert_eq!(
+        Indicators::from_history(&[], &IndicatorConfig::default()),
+        None
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_indicators_over_missing_days() {
+    // 40 days with days 31 to 33 missing, the last 7 closes are days 34 to 40
+    let mut history = daily(&(1..=40).map(|i| i as f64).collect::<Vec<_>>());
+    history.drain(30..33);
+    let indicators = Indicators::from_history(&history, &IndicatorConfig::default()).unwrap();
+
+    // By date, not by record count, day 33 is missing
+    assert_eq!(indicators.change_7d, None);
+    assert_close(indicators.change_30d, 300.0);
+    assert_close(indicators.sma[0].1, 37.0);
+    assert_eq!(indicators.sma[1].1, None);
+    assert_eq!(indicators.rsi, None);
+}
+
+#[cfg(test)]
+#[test]
+fn test_summary_state_follows_history() {
+    let config = IndicatorConfig::default();
+    let rising = daily(&(1..=40).map(|i| i as f64).collect::<Vec<_>>());
+    let mut zigzag = daily(
+        &(0..40)
+            .map(|i| {
+                100.0
+                    + if i % 3 == 0 {
+                        -(i as f64)
+                    } else {
+                        i as f64 / 2.0
+                    }
+            })
+            .collect::<Vec<_>>(),
+    );
+    let mut refilled = zigzag.clone();
+    zigzag.drain(30..33);
+    refilled.drain(10..12);
+
+    for history in [&rising, &zigzag, &refilled, &rising[..15].to_vec()] {
+        let state = SummaryState::from_history(history, config.rsi_period);
+        let indicators = Indicators::from_history(history, &config).unwrap();
+        assert_eq!(state.summary(config.rsi_period), summary_of(&indicators));
+    }
+
+    // The close of the latest day is replaced, not taken as another day
+    let mut state = SummaryState::from_history(&rising, config.rsi_period);
+    let (end, _) = *rising.last().unwrap();
+    state.update(end, 35.0, config.rsi_period);
+    state.update(end - Duration::days(1), 1.0, config.rsi_period);
+    let mut lower = rising.clone();
+    lower.last_mut().unwrap().1 = DailyOhlc::new(35.0);
+    assert_eq!(
+        state.summary(config.rsi_period),
+        summary_of(&Indicators::from_history(&lower, &config).unwrap())
+    );
+    assert_eq!(state.closes.len(), 8);
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_record_indicator_summary_reads_history_once() {
+    use crate::{catboard::history::record_daily_price, kv::memory::MemoryKv};
+
+    let kv = MemoryKv::default();
+    let pair = "Crypto.SOL/USD";
+    let history = daily(&(1..=40).map(|i| i as f64).collect::<Vec<_>>());
+    for (date, ohlc) in history.iter() {
+        record_daily_price(&kv, pair, *date, ohlc.close).await;
+    }
+    let (end, _) = *history.last().unwrap();
+    record_indicator_summary(&kv, pair, end, 40.0).await;
+    assert_eq!(
+        get_indicator_summary(&kv, pair).await.unwrap(),
+        "24h `+2.56%` 📈 · 7d `+21.21%` 📈 · RSI `100`"
+    );
+
+    // Without the history the next day still advances from the state, average gain 13/14 and
+    // loss 20/14
+    for (date, _) in history.iter() {
+        kv.delete(&keys::daily_price(pair, *date)).await.unwrap();
+    }
+    record_indicator_summary(&kv, pair, end + Duration::days(1), 20.0).await;
+    assert_eq!(
+        get_indicator_summary(&kv, pair).await.unwrap(),
+        "24h `-50.00%` 📉 · 7d `-41.18%` 📉 · RSI `39`"
+    );
+}
@@ -0,0 +1,221 @@
+//! One-off move of the records kept in `DEVNET_PYTH_PRODUCTS` before every role got its own
+//! namespace, run with `/api/migrate_kv` and the `API_KEY`.
+//!
+//! Daily prices move to `DEVNET_PYTH_PRICES`, watchlists to `USERS`, and `Crypto.SOL/USD:alerts`
+//! lists are split into one key per alert. Records are copied with their expiration and left in
+//! place, keys already in the new namespace win so the migration can be run again.
+
+use chrono::NaiveDate;
+use serde_json::json;
+
+use crate::{
+    catboard::alert::PriceAlert,
+    error::Error,
+    kv::{
+        bindings::Bindings,
+        json::{get_json, put_json},
+        keys, KeyValueStore, ListKey, PutOptions,
+    },
+    solana::web3::Cluster,
+};
+
+#[derive(Debug, Default, PartialEq)]
+struct MigrationReport {
+    prices: usize,
+    watchlists: usize,
+    alerts: usize,
+    /// Already in the new namespace.
+    skipped: usize,
+}
+
+/// Same expiration as `key` had, none when it didn't expire.
+fn expiration_of(key: &ListKey) -> PutOptions {
+    match key.expiration {
+        Some(expiration) => PutOptions::expire_at(expiration),
+        None => PutOptions::default(),
+    }
+}
+
+/// `Crypto.SOL/USD:2022-01-01` is a daily price.
+fn is_daily_price(name: &str) -> bool {
+    match name.rsplit_once(':') {
+        Some((_, date)) => NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok(),
+        None => false,
+    }
+}
+
+fn is_watchlist(name: &str) -> bool {
+    name.starts_with("user:") && name.ends_with(":watchlist")
+}
+
+/// Copy `key` as is unless `to` has it already, `true` when copied.
+async fn copy_key(
+    from: &dyn KeyValueStore,
+    to: &dyn KeyValueStore,
+    key: &ListKey,
+) -> Result<bool, Error> {
+    if to.get_vec(&key.name).await?.is_some() {
+        return Ok(false);
+    }
+    match from.get_vec(&key.name).await? {
+        Some(value) => to
+            .put(&key.name, &value, expiration_of(key))
+            .await
+            .map(|_| true),
+        None => Ok(false),
+    }
+}
+
+/// Split the legacy `Crypto.SOL/USD:alerts` list at `key` into `alert:Crypto.SOL/USD:1234:SOL-1`.
+async fn split_alerts(
+    from: &dyn KeyValueStore,
+    users: &dyn KeyValueStore,
+    key: &ListKey,
+    pair: &str,
+    report: &mut MigrationReport,
+) -> Result<(), Error> {
+    let alerts: Vec<PriceAlert> = get_json(from, &key.name).await?.unwrap_or_default();
+    for alert in alerts {
+        let alert_key = keys::alert(pair, &alert.user_id, &alert.id);
+        if users.get_vec(&alert_key).await?.is_some() {
+            report.skipped += 1;
+            continue;
+        }
+        put_json(users, &alert_key, &alert, expiration_of(key)).await?;
+        report.alerts += 1;
+    }
+
+    Ok(())
+}
+
+/// Move what the bot kept in the devnet products namespace to the namespace of its role.
+pub(crate) async fn migrate_devnet_products(bindings: &Bindings) -> Result<String, Error> {
+    let products = bindings.products(&Cluster::Devnet);
+    let prices = bindings.prices(&Cluster::Devnet);
+    let users = bindings.users();
+    let mut report = MigrationReport::default();
+
+    for key in products.list_all("").await? {
+        let name = key.name.as_str();
+        if let Some(pair) = name.strip_suffix(":alerts") {
+            split_alerts(products, users, &key, pair, &mut report).await?;
+        } else if is_watchlist(name) {
+            match copy_key(products, users, &key).await? {
+                true => report.watchlists += 1,
+                false => report.skipped += 1,
+            }
+        } else if is_daily_price(name) {
+            match copy_key(products, prices, &key).await? {
+                true => report.prices += 1,
+                false => report.skipped += 1,
+            }
+        }
+    }
+
+    // Lists written to `USERS` before alerts got a key each
+    for key in users.list_all("").await? {
+        if let Some(pair) = key.name.strip_suffix(":alerts") {
+            split_alerts(users, users, &key, pair, &mut report).await?;
+        }
+    }
+
+    Ok(json!({
+        "prices": report.prices,
+        "watchlists": report.watchlists,
+        "alerts": report.alerts,
+        "skipped": report.skipped,
+    })
+    .to_string())
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_migrate_devnet_products() {
+    use crate::catboard::{alert::list_alerts_by_user, watchlist::get_watchlist};
+
+    let bindings = Bindings::in_memory();
+    let products = bindings.products(&Cluster::Devnet);
+    let alert = |id: &str, user_id: &str| {
+        json!({
+            "id": id, "symbol": "SOL", "direction": "above", "price": 100.0,
+            "user_id": user_id, "channel_id": "9",
+        })
+    };
+    // Left where they are
+    for (key, value) in [
+        (keys::SYMBOLS.to_string(), "Crypto.SOL/USD"),
+        (
+            keys::price_account("Crypto.SOL/USD"),
+            "J83w4HKfqxwcq3BEMMkPFSppX3gqekLyLJBexebFVkix",
+        ),
+    ] {
+        products.put_text(&key, value, 60).await.unwrap();
+    }
+    for (key, value) in [
+        (
+            "Crypto.SOL/USD:2022-01-01".to_string(),
+            json!({ "version": 1, "data": { "open": 1.0, "high": 2.0, "low": 1.0, "close": 2.0 } }),
+        ),
+        (keys::watchlist("1"), json!(["BTC", "SOL"])),
+        (keys::watchlist("2"), json!(["ETH"])),
+        (
+            "Crypto.SOL/USD:alerts".to_string(),
+            json!({ "version": 1, "data": [alert("SOL-1", "1"), alert("SOL-2", "2")] }),
+        ),
+    ] {
+        products
+            .put(
+                &key,
+                value.to_string().as_bytes(),
+                PutOptions::expire_at(4_102_444_800),
+            )
+            .await
+            .unwrap();
+    }
+    // Written since, kept over the old one
+    bindings
+        .users()
+        .put_text(&keys::watchlist("2"), r#"["DOGE"]"#, 60)
+        .await
+        .unwrap();
+
+    let report = migrate_devnet_products(&bindings).await.unwrap();
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&report).unwrap(),
+        json!({ "prices": 1, "watchlists": 1, "alerts": 2, "skipped": 1 })
+    );
+
+    let prices = bindings.prices(&Cluster::Devnet);
+    let moved = prices.list_all("Crypto.SOL/USD:").await.unwrap();
+    assert_eq!(moved[0].name, "Crypto.SOL/USD:2022-01-01");
+    assert_eq!(moved[0].expiration, Some(4_102_444_800));
+    assert_eq!(
+        get_watchlist(bindings.users(), "1").await.unwrap(),
+        vec!["BTC", "SOL"]
+    );
+    assert_eq!(
+        get_watchlist(bindings.users(), "2").await.unwrap(),
+        vec!["DOGE"]
+    );
+    let alerts = list_alerts_by_user(&bindings, "2").await.unwrap();
+    assert_eq!(alerts[0].id, "SOL-2");
+    assert!(bindings
+        .users()
+        .list_all(&keys::watchlist("1"))
+        .await
+        .unwrap()
+        .iter()
+        .all(|key| key.expiration == Some(4_102_444_800)));
+    assert!(products
+        .get_text(&keys::price_account("Crypto.SOL/USD"))
+        .await
+        .unwrap()
+        .is_some());
+
+    // Again, nothing left to move
+    let report = migrate_devnet_products(&bindings).await.unwrap();
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&report).unwrap(),
+        json!({ "prices": 0, "watchlists": 0, "alerts": 0, "skipped": 5 })
+    );
+}
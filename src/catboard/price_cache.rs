@@ -0,0 +1,253 @@
+//! Latest Pyth prices kept in KV, served while fresh and refreshed after the reply once stale.
+
+use chrono::Utc;
+use pyth_client::PriceConf;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::Error,
+    kv::{
+        json::{get_json, put_json, Versioned},
+        keys, KeyValueStore, PutOptions,
+    },
+    pyth::adaptor::fetch_pyth_price_by_pubkey,
+    solana::{pubkey::Pubkey, web3::Cluster},
+    task::Task,
+};
+
+/// Seconds past `max_age` a stale price is still served while it's refreshed.
+const STALE_WHILE_REVALIDATE: i64 = 60;
+
+/// Workers KV keeps values at least this long.
+const MIN_TTL: i64 = 60;
+
+/// Workers KV reads can lag a write by this long at other edge locations, a newer price may
+/// already be cached elsewhere, so no max age is shorter.
+const KV_PROPAGATION_DELAY: i64 = 60;
+
+/// Seconds a price of `pair` is served from the cache without asking Pyth again.
+pub(crate) fn max_age(pair: &str) -> i64 {
+    let max_age = match pair {
+        // Pegged, barely moves.
+        "Crypto.USDC/USD" | "Crypto.USDT/USD" => 300,
+        "Crypto.BTC/USD" | "Crypto.ETH/USD" | "Crypto.SOL/USD" => 60,
+        _ => 120,
+    };
+
+    max_age.max(KV_PROPAGATION_DELAY)
+}
+
+/// Kept at `Crypto.SOL/USD:cached_price`, `fetched_at` in seconds since the epoch.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+struct CachedPrice {
+    price: i64,
+    conf: u64,
+    expo: i32,
+    fetched_at: i64,
+}
+
+impl CachedPrice {
+    fn new(price_conf: &PriceConf, fetched_at: i64) -> Self {
+        CachedPrice {
+            price: price_conf.price,
+            conf: price_conf.conf,
+            expo: price_conf.expo,
+            fetched_at,
+        }
+    }
+
+    fn price_conf(&self) -> PriceConf {
+        PriceConf {
+            price: self.price,
+            conf: self.conf,
+            expo: self.expo,
+        }
+    }
+}
+
+impl Versioned for CachedPrice {
+    const VERSION: u32 = 1;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum PriceSource {
+    /// Just fetched from Pyth.
+    Live,
+    /// From the cache, within its max age.
+    Cached,
+    /// From the cache past its max age, refreshed once the reply is sent.
+    Stale,
+}
+
+/// Where a price came from, `age` in seconds.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct PriceLookup {
+    pub(crate) price_conf: PriceConf,
+    pub(crate) source: PriceSource,
+    pub(crate) age: i64,
+}
+
+impl PriceLookup {
+    /// Shown next to the price, e.g. "🗄️ cached `3s` ago".
+    pub(crate) fn indicator(&self) -> String {
+        match self.source {
+            PriceSource::Live => "⚡ live".to_string(),
+            PriceSource::Cached => format!("🗄️ cached `{}s` ago", self.age),
+            PriceSource::Stale => format!("⏳ cached `{}s` ago, refreshing", self.age),
+        }
+    }
+}
+
+/// How a price `age` seconds old can be served, `None` once too old to serve at all.
+fn freshness(age: i64, max_age: i64) -> Option<PriceSource> {
+    if age <= max_age {
+        Some(PriceSource::Cached)
+    } else if age <= max_age + STALE_WHILE_REVALIDATE {
+        Some(PriceSource::Stale)
+    } else {
+        None
+    }
+}
+
+/// Keep `price_conf` as the latest price of `pair`, fetched at `now`.
+pub(crate) async fn cache_price(
+    kv: &dyn KeyValueStore,
+    pair: &str,
+    price_conf: &PriceConf,
+    now: i64,
+) {
+    let ttl = (max_age(pair) + STALE_WHILE_REVALIDATE).max(MIN_TTL);
+    put_json(
+        kv,
+        &keys::cached_price(pair),
+        &CachedPrice::new(price_conf, now),
+        PutOptions::ttl(ttl as u64),
+    )
+    .await
+    .unwrap_or_default();
+}
+
+/// Fetch the latest price of `pair` from Pyth and cache it.
+pub(crate) async fn refresh_cached_price(
+    kv: &dyn KeyValueStore,
+    cluster: &Cluster,
+    pair: &str,
+    px_pkey: &Pubkey,
+) -> Result<Option<PriceConf>, Error> {
+    let price_conf = match fetch_pyth_price_by_pubkey(cluster, px_pkey).await? {
+        Some(price_conf) => price_conf,
+        None => return Ok(None),
+    };
+    cache_price(kv, pair, &price_conf, Utc::now().timestamp()).await;
+
+    Ok(Some(price_conf))
+}
+
+/// Refetch the stale cached price of `pair` on `cluster` from `rpc` once the reply is sent.
+pub(crate) fn revalidate_price(
+    cluster: Cluster,
+    rpc: Cluster,
+    pair: String,
+    px_pkey: Pubkey,
+) -> Task {
+    Task::new(move |bindings| async move {
+        refresh_cached_price(bindings.prices(&cluster), &rpc, &pair, &px_pkey)
+            .await?
+            .map(|_| ())
+            .ok_or_else(|| Error::NotFound(format!("price of {}", pair)))
+    })
+}
+
+async fn lookup_price_at(
+    kv: &dyn KeyValueStore,
+    cluster: &Cluster,
+    pair: &str,
+    px_pkey: &Pubkey,
+    now: i64,
+) -> Result<Option<PriceLookup>, Error> {
+    let cached: Option<CachedPrice> = get_json(kv, &keys::cached_price(pair))
+        .await
+        .unwrap_or_default();
+    if let Some(cached) = cached {
+        let age = (now - cached.fetched_at).max(0);
+        if let Some(source) = freshness(age, max_age(pair)) {
+            return Ok(Some(PriceLookup {
+                price_conf: cached.price_conf(),
+                source,
+                age,
+            }));
+        }
+    }
+
+    let price_conf = match refresh_cached_price(kv, cluster, pair, px_pkey).await? {
+        Some(price_conf) => price_conf,
+        None => return Ok(None),
+    };
+
+    Ok(Some(PriceLookup {
+        price_conf,
+        source: PriceSource::Live,
+        age: 0,
+    }))
+}
+
+/// Latest price of `pair`, from the cache while fresh enough and from Pyth otherwise.
+/// A `Stale` price should be refreshed with `revalidate_price` after replying, `None` when Pyth
+/// has no current price.
+pub(crate) async fn lookup_price(
+    kv: &dyn KeyValueStore,
+    cluster: &Cluster,
+    pair: &str,
+    px_pkey: &Pubkey,
+) -> Result<Option<PriceLookup>, Error> {
+    lookup_price_at(kv, cluster, pair, px_pkey, Utc::now().timestamp()).await
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_lookup_serves_fresh_and_stale_from_cache() {
+    use crate::kv::memory::MemoryKv;
+
+    let kv = MemoryKv::default();
+    let pair = "Crypto.SOL/USD";
+    let price_conf = PriceConf {
+        price: 15_000_000_000,
+        conf: 5_000_000,
+        expo: -8,
+    };
+    let now = 1_640_995_200;
+    cache_price(&kv, pair, &price_conf, now).await;
+
+    // Neither lookup reaches Pyth, the pubkey is never read.
+    for (elapsed, source) in [(3, PriceSource::Cached), (90, PriceSource::Stale)] {
+        let lookup = lookup_price_at(
+            &kv,
+            &Cluster::Devnet,
+            pair,
+            &Pubkey::default(),
+            now + elapsed,
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            lookup,
+            PriceLookup {
+                price_conf: price_conf.clone(),
+                source,
+                age: elapsed,
+            }
+        );
+    }
+
+    assert_eq!(freshness(60, max_age(pair)), Some(PriceSource::Cached));
+    assert_eq!(freshness(61, max_age(pair)), Some(PriceSource::Stale));
+    assert_eq!(freshness(121, max_age(pair)), None);
+    assert_eq!(
+        freshness(200, max_age("Crypto.USDC/USD")),
+        Some(PriceSource::Cached)
+    );
+    assert!(["Crypto.SOL/USD", "Crypto.USDC/USD", "Crypto.JUP/USD"]
+        .iter()
+        .all(|pair| max_age(pair) >= KV_PROPAGATION_DELAY));
+}
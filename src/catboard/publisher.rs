@@ -1,8 +1,13 @@
-use std::collections::HashMap;
-
 use crate::{
-    cloudflare::worker::WorkersKv,
+    catboard::{
+        alert::check_alerts, history::record_daily_price, indicators::record_indicator_summary,
+        price_cache::cache_price,
+    },
+    discord::rest::DiscordClient,
+    endpoints::Endpoints,
     error::Error,
+    kv::{bindings::Bindings, keys, KeyValueStore},
+    marinade::state::{fetch_marinade_state, MSOL_PAIR},
     pyth::adaptor::{fetch_pyth_price_by_pubkey, fetch_pyth_product_account_by_symbol},
     solana::{pubkey::Pubkey, web3::Cluster},
 };
@@ -27,68 +32,114 @@ impl RecordPrice {
     }
 }
 
-// TODO: finish this
+async fn record_synced_at(kv: &dyn KeyValueStore) {
+    kv.put_text(
+        keys::LAST_SYNCED_AT,
+        &Utc::now().to_rfc3339(),
+        60 * 60 * 24 * 365,
+    )
+    .await
+    .unwrap_or_default();
+}
+
+/// Merge the mSOL price in SOL on mainnet-beta, read from `rpc`, into the record of `date`.
+async fn record_msol_price(
+    kv: &dyn KeyValueStore,
+    rpc: &Cluster,
+    date: NaiveDate,
+) -> Result<(), Error> {
+    let state = fetch_marinade_state(rpc).await?;
+    record_daily_price(kv, MSOL_PAIR, date, state.msol_price()).await;
+
+    Ok(())
+}
+
+/// Record the latest price of every symbol and post the alerts it fired through `notifier`.
 pub(crate) async fn fetch_pyth_prices_and_record(
-    kv: &WorkersKv,
-    _cluster: &Cluster,
-    _symbols: Vec<&str>,
+    bindings: &Bindings,
+    endpoints: &Endpoints,
+    cluster: &Cluster,
+    symbols: Vec<&str>,
+    notifier: Option<&DiscordClient>,
 ) -> Result<String, Error> {
-    // BTC, SOL, ETH, BNB
-    let price_account_map = HashMap::from([
-        (
-            "Crypto.BTC/USD".to_string(),
-            "HovQMDrbAgAYPCmHVSrezcSmkMtXSSUsLDFANExrZh2J".to_string(),
-        ),
-        (
-            "Crypto.ETH/USD".to_string(),
-            "EdVCmQ9FSPcVe5YySXDPCRmc8aDQLKJ9xvYBMZPie1Vw".to_string(),
-        ),
-    ]);
-
-    // loop fetch
-    // let mut prices: Vec<RecordPrice> = Vec::new();
-    for (symbol, px_pkey) in price_account_map.iter() {
-        // pubkey
-        let pubkey = Pubkey::from_str(px_pkey).unwrap();
+    let today = Utc::now().naive_utc().date();
+    let products = bindings.products(cluster);
+    let rpc = endpoints.rpc(cluster);
+    let mut price_recorded = 0;
+    let mut alert_fired = 0;
+
+    for symbol in symbols.iter() {
+        // Price account from last product sync
+        let key = keys::price_account(symbol);
+        let px_pkey = products.get_text(&key).await.unwrap_or_default();
+        let pubkey = match px_pkey.as_deref().map(Pubkey::from_str) {
+            Some(Ok(pubkey)) => pubkey,
+            _ => continue,
+        };
 
         // fetch
-        let price_conf = fetch_pyth_price_by_pubkey(&Cluster::Devnet, &pubkey).await;
+        // Not trading or unreadable, the other symbols are still recorded
+        let price_conf = match fetch_pyth_price_by_pubkey(&rpc, &pubkey).await {
+            Ok(Some(price_conf)) => price_conf,
+            _ => continue,
+        };
+        let price = price_conf.price as f64 * 10f64.powi(price_conf.expo);
 
-        let price = price_conf.unwrap().price.to_string();
-        // prices.push(RecordPrice::new(symbol, px_pkey));
+        // `Crypto.SOL/USD:cached_price`, warm for `/price`
+        let prices = bindings.prices(cluster);
+        cache_price(prices, symbol, &price_conf, Utc::now().timestamp()).await;
 
         // kv
-        // `price:Crypto.SOL/USD:2022-01-01` = `{index,low,open,close,average,high}`
-        let utc = Utc::now().to_string();
-        let today: Vec<&str> = utc.split('T').collect();
-        let key = format!("{}:{}", symbol, today[0]);
-        let value = json!({
-            "close": price,
-        });
-
-        kv.put_text(&key, value.as_str().unwrap(), 60 * 60 * 24 * 365)
-            .await
-            .unwrap_or_default();
+        // `Crypto.SOL/USD:2022-01-01` = `{open,high,low,close}`
+        record_daily_price(prices, symbol, today, price).await;
+        price_recorded += 1;
+
+        // `Crypto.SOL/USD:indicators`, read by `/price`
+        record_indicator_summary(prices, symbol, today, price).await;
+
+        // `alert:Crypto.SOL/USD:*`
+        alert_fired += check_alerts(bindings.users(), notifier, symbol, price).await;
     }
 
-    Ok("ok".to_string())
+    // `Marinade.mSOL/SOL:2022-01-01`, read by `/stake` for the APY
+    let msol_recorded = record_msol_price(
+        bindings.prices(&Cluster::MainnetBeta),
+        &endpoints.rpc(&Cluster::MainnetBeta),
+        today,
+    )
+    .await
+    .is_ok();
+
+    record_synced_at(products).await;
+
+    Ok(json!({
+        "completed": price_recorded,
+        "alerts": alert_fired,
+        "msol": msol_recorded,
+    })
+    .to_string())
 }
 
 pub(crate) async fn fetch_pyth_product_and_record(
-    kv: &WorkersKv,
+    bindings: &Bindings,
+    endpoints: &Endpoints,
     cluster: &Cluster,
     symbols: Vec<&str>,
 ) -> Result<String, Error> {
+    let kv = bindings.products(cluster);
+    let rpc = endpoints.rpc(cluster);
     // TOFIX : script exceeded time limit when symbols > 3
     // let symbols = ["Crypto.BTC/USD", "Crypto.ETH/USD", "Crypto.SOL/USD"];
     let mut product_fetched = 0;
     for symbol in symbols.iter() {
         // Get product account from Pyth
-        let product_account = fetch_pyth_product_account_by_symbol(cluster, symbol).await;
+        let product_account = match fetch_pyth_product_account_by_symbol(&rpc, symbol).await? {
+            Some(product_account) => product_account.to_string(),
+            None => continue,
+        };
 
         // Write to KV
-        let key = format!("{}:price_account", symbol);
-        let product_account = product_account.unwrap().to_string();
+        let key = keys::price_account(symbol);
         kv.put_text(&key, product_account.as_str(), 60 * 60 * 24 * 365)
             .await
             .unwrap_or_default();
@@ -96,6 +147,39 @@ pub(crate) async fn fetch_pyth_product_and_record(
         product_fetched += 1;
     }
 
+    record_synced_at(kv).await;
+
     // Result
     Ok(json!({ "completed": product_fetched }).to_string())
 }
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_sync_prices_records_msol_price() {
+    use crate::{catboard::history::read_daily_history, testing::e2e::Harness};
+
+    let harness = Harness::start().await;
+    let result = fetch_pyth_prices_and_record(
+        &harness.bindings,
+        &harness.endpoints,
+        &Cluster::Devnet,
+        vec!["Crypto.SOL/USD"],
+        None,
+    )
+    .await
+    .unwrap();
+    let result: serde_json::Value = serde_json::from_str(&result).unwrap();
+    assert_eq!(result["completed"], 1);
+    assert_eq!(result["msol"], true);
+
+    let today = Utc::now().naive_utc().date();
+    let history = read_daily_history(
+        harness.bindings.prices(&Cluster::MainnetBeta),
+        MSOL_PAIR,
+        today,
+        1,
+    )
+    .await;
+    assert_eq!(history.len(), 1);
+    assert!((history[0].1.close - 1.18).abs() < 1e-9);
+}
@@ -0,0 +1,148 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    catboard::watchlist::{fetch_watchlist_prices, to_usd},
+    endpoints::Endpoints,
+    error::Error,
+    jupiter::token::find_token_by_mint,
+    kv::bindings::Bindings,
+    solana::{
+        pubkey::Pubkey,
+        token::{decode_mint_decimals, TokenAccount, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID},
+        web3::{get_balance, get_multiple_account_data, get_token_accounts_by_owner, Cluster},
+    },
+};
+
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+
+/// Balance of one token, `usd` is `None` when there is no Pyth price for it.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Holding {
+    pub(crate) symbol: String,
+    pub(crate) amount: f64,
+    pub(crate) usd: Option<f64>,
+}
+
+pub(crate) struct Portfolio {
+    pub(crate) holdings: Vec<Holding>,
+}
+
+impl Portfolio {
+    pub(crate) fn total_usd(&self) -> f64 {
+        self.holdings.iter().filter_map(|holding| holding.usd).sum()
+    }
+}
+
+/// Known symbol of a mint, otherwise the shortened mint like `7xKX…gAsU`.
+fn symbol_of_mint(mint: &Pubkey) -> String {
+    let mint = mint.to_string();
+    match find_token_by_mint(&mint) {
+        Some(token) => token.symbol.to_string(),
+        None => format!("{}…{}", &mint[..4], &mint[mint.len() - 4..]),
+    }
+}
+
+/// Sum balances per symbol, so wrapped SOL lands on SOL, in symbol order.
+fn group_holdings(balances: Vec<(String, f64)>) -> Vec<Holding> {
+    let mut amounts: BTreeMap<String, f64> = BTreeMap::new();
+    for (symbol, amount) in balances {
+        if amount > 0.0 {
+            *amounts.entry(symbol).or_default() += amount;
+        }
+    }
+
+    amounts
+        .into_iter()
+        .map(|(symbol, amount)| Holding {
+            symbol,
+            amount,
+            usd: None,
+        })
+        .collect()
+}
+
+/// Most valuable first, unpriced holdings last.
+fn sort_holdings(holdings: &mut [Holding]) {
+    holdings.sort_by(|a, b| {
+        b.usd
+            .unwrap_or(-1.0)
+            .partial_cmp(&a.usd.unwrap_or(-1.0))
+            .unwrap()
+            .then_with(|| a.symbol.cmp(&b.symbol))
+    });
+}
+
+/// SOL, SPL Token and Token-2022 balances of `owner` on mainnet-beta, valued with Pyth prices from `price_cluster`.
+pub(crate) async fn fetch_portfolio(
+    bindings: &Bindings,
+    endpoints: &Endpoints,
+    price_cluster: &Cluster,
+    owner: &Pubkey,
+) -> Result<Portfolio, Error> {
+    let cluster = endpoints.rpc(&Cluster::MainnetBeta);
+    let lamports = get_balance(&cluster, owner).await?;
+    let mut token_accounts = Vec::new();
+    for program_id in [TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID].iter() {
+        let accounts = get_token_accounts_by_owner(&cluster, owner, program_id).await?;
+        token_accounts.extend(
+            accounts
+                .iter()
+                .filter_map(|data| TokenAccount::decode(data).ok()),
+        );
+    }
+
+    // Decimals live on the mint accounts
+    let mut mints: Vec<Pubkey> = token_accounts.iter().map(|account| account.mint).collect();
+    mints.sort();
+    mints.dedup();
+    let decimals: BTreeMap<Pubkey, u8> = mints
+        .iter()
+        .zip(get_multiple_account_data(&cluster, &mints).await?)
+        .filter_map(|(mint, data)| Some((*mint, decode_mint_decimals(&data?).ok()?)))
+        .collect();
+
+    let mut balances = vec![("SOL".to_string(), lamports as f64 / LAMPORTS_PER_SOL)];
+    for account in &token_accounts {
+        if let Some(decimals) = decimals.get(&account.mint) {
+            let amount = account.amount as f64 / 10f64.powi(*decimals as i32);
+            balances.push((symbol_of_mint(&account.mint), amount));
+        }
+    }
+    let mut holdings = group_holdings(balances);
+
+    let symbols: Vec<String> = holdings.iter().map(|h| h.symbol.clone()).collect();
+    let prices = fetch_watchlist_prices(bindings, endpoints, price_cluster, &symbols).await?;
+    for (holding, (_, price_conf)) in holdings.iter_mut().zip(prices) {
+        holding.usd = price_conf
+            .as_ref()
+            .map(to_usd)
+            .map(|(price, _)| price * holding.amount);
+    }
+    sort_holdings(&mut holdings);
+
+    Ok(Portfolio { holdings })
+}
+
+#[cfg(test)]
+#[test]
+fn test_group_and_sort_holdings() {
+    let mut holdings = group_holdings(vec![
+        ("SOL".to_string(), 1.5),
+        ("USDC".to_string(), 20.0),
+        ("SOL".to_string(), 0.5),
+        ("BONK".to_string(), 0.0),
+        ("7xKX…gAsU".to_string(), 3.0),
+    ]);
+    holdings[1].usd = Some(200.0);
+    holdings[2].usd = Some(20.0);
+    sort_holdings(&mut holdings);
+
+    let symbols: Vec<&str> = holdings.iter().map(|h| h.symbol.as_str()).collect();
+    assert_eq!(symbols, vec!["SOL", "USDC", "7xKX…gAsU"]);
+    assert_eq!(holdings[0].amount, 2.0);
+    assert_eq!(
+        Portfolio { holdings }.total_usd(),
+        220.0,
+        "unpriced holdings are left out"
+    );
+}
@@ -0,0 +1,204 @@
+use pyth_client::PriceConf;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::str::FromStr;
+
+use crate::{
+    catboard::consumer::get_price_account_from_kv_by_symbol,
+    endpoints::Endpoints,
+    error::Error,
+    kv::{
+        bindings::Bindings,
+        json::{get_json, put_json, Versioned},
+        keys, KeyValueStore, PutOptions,
+    },
+    pyth::adaptor::fetch_pyth_prices_by_pubkeys,
+    solana::{pubkey::Pubkey, web3::Cluster},
+};
+
+const MAX_WATCHLIST_SYMBOLS: usize = 20;
+
+/// Symbols watched by a user, kept at `user:1234:watchlist`.
+#[derive(Debug, Default, PartialEq, Deserialize, Serialize)]
+struct StoredWatchlist {
+    symbols: Vec<String>,
+}
+
+impl Versioned for StoredWatchlist {
+    const VERSION: u32 = 1;
+
+    // Unversioned watchlists were a bare `["BTC","SOL"]`
+    fn migrate(version: u32, data: Value) -> Option<Value> {
+        match version {
+            0 => Some(serde_json::json!({ "symbols": data })),
+            _ => Some(data),
+        }
+    }
+}
+
+/// Watched symbols, an error when the stored watchlist can't be read so it isn't overwritten.
+pub(crate) async fn get_watchlist(
+    kv: &dyn KeyValueStore,
+    user_id: &str,
+) -> Result<Vec<String>, Error> {
+    Ok(get_json::<StoredWatchlist>(kv, &keys::watchlist(user_id))
+        .await?
+        .unwrap_or_default()
+        .symbols)
+}
+
+async fn put_watchlist(
+    kv: &dyn KeyValueStore,
+    user_id: &str,
+    symbols: &[String],
+) -> Result<(), Error> {
+    let watchlist = StoredWatchlist {
+        symbols: symbols.to_vec(),
+    };
+    put_json(
+        kv,
+        &keys::watchlist(user_id),
+        &watchlist,
+        PutOptions::ttl(60 * 60 * 24 * 365),
+    )
+    .await
+}
+
+fn insert_symbol(symbols: &mut Vec<String>, symbol: &str) -> Result<(), Error> {
+    let symbol = symbol.trim().to_uppercase();
+    if symbols.contains(&symbol) {
+        return Err(Error::InvalidPayload(format!(
+            "`{}` is already watched",
+            symbol
+        )));
+    }
+    if symbols.len() >= MAX_WATCHLIST_SYMBOLS {
+        return Err(Error::InvalidPayload(format!(
+            "a watchlist holds up to {} symbols",
+            MAX_WATCHLIST_SYMBOLS
+        )));
+    }
+
+    symbols.push(symbol);
+    Ok(())
+}
+
+pub(crate) async fn add_to_watchlist(
+    kv: &dyn KeyValueStore,
+    user_id: &str,
+    symbol: &str,
+) -> Result<Vec<String>, Error> {
+    let mut symbols = get_watchlist(kv, user_id).await?;
+    insert_symbol(&mut symbols, symbol)?;
+    put_watchlist(kv, user_id, &symbols).await?;
+
+    Ok(symbols)
+}
+
+pub(crate) async fn remove_from_watchlist(
+    kv: &dyn KeyValueStore,
+    user_id: &str,
+    symbol: &str,
+) -> Result<Vec<String>, Error> {
+    let symbol = symbol.trim().to_uppercase();
+    let mut symbols = get_watchlist(kv, user_id).await?;
+
+    let count = symbols.len();
+    symbols.retain(|watched| *watched != symbol);
+    if symbols.len() == count {
+        return Err(Error::NotFound(format!("{} in watchlist", symbol)));
+    }
+
+    if symbols.is_empty() {
+        kv.delete(&keys::watchlist(user_id)).await?;
+    } else {
+        put_watchlist(kv, user_id, &symbols).await?;
+    }
+    Ok(symbols)
+}
+
+/// Price and confidence in USD.
+pub(crate) fn to_usd(price_conf: &PriceConf) -> (f64, f64) {
+    let scale = 10f64.powi(price_conf.expo);
+    (
+        price_conf.price as f64 * scale,
+        price_conf.conf as f64 * scale,
+    )
+}
+
+/// Latest price of each symbol, `None` when it isn't synced or has no current price.
+pub(crate) async fn fetch_watchlist_prices(
+    bindings: &Bindings,
+    endpoints: &Endpoints,
+    cluster: &Cluster,
+    symbols: &[String],
+) -> Result<Vec<(String, Option<PriceConf>)>, Error> {
+    // Price accounts from last product sync
+    let mut px_pkeys = Vec::new();
+    let mut indexes = Vec::new();
+    for (index, symbol) in symbols.iter().enumerate() {
+        let price_account =
+            get_price_account_from_kv_by_symbol(bindings.products(cluster), symbol).await;
+        if let Ok(pubkey) = Pubkey::from_str(&price_account) {
+            px_pkeys.push(pubkey);
+            indexes.push(index);
+        }
+    }
+
+    let mut prices: Vec<(String, Option<PriceConf>)> = symbols
+        .iter()
+        .map(|symbol| (symbol.clone(), None))
+        .collect();
+    let price_confs = fetch_pyth_prices_by_pubkeys(&endpoints.rpc(cluster), &px_pkeys).await?;
+    for (index, price_conf) in indexes.into_iter().zip(price_confs) {
+        prices[index].1 = price_conf;
+    }
+
+    Ok(prices)
+}
+
+#[cfg(test)]
+#[test]
+fn test_insert_symbol() {
+    let mut symbols = vec!["SOL".to_string()];
+
+    insert_symbol(&mut symbols, " btc ").unwrap();
+    assert_eq!(symbols, vec!["SOL", "BTC"]);
+    assert!(insert_symbol(&mut symbols, "sol").is_err());
+
+    let mut full: Vec<String> = (0..MAX_WATCHLIST_SYMBOLS).map(|i| i.to_string()).collect();
+    assert!(insert_symbol(&mut full, "ETH").is_err());
+}
+
+#[cfg(test)]
+#[test]
+fn test_migrate_unversioned_watchlist() {
+    use crate::kv::json::decode;
+
+    assert_eq!(
+        decode::<StoredWatchlist>(r#"["BTC","SOL"]"#).unwrap(),
+        Some(StoredWatchlist {
+            symbols: vec!["BTC".to_string(), "SOL".to_string()]
+        })
+    );
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_unreadable_watchlist_is_not_overwritten() {
+    use crate::kv::memory::MemoryKv;
+
+    let kv = MemoryKv::default();
+    let stored = r#"{"version":9,"data":{"symbols":["BTC"]}}"#;
+    kv.put_text(&keys::watchlist("7"), stored, 60)
+        .await
+        .unwrap();
+
+    assert!(get_watchlist(&kv, "7").await.is_err());
+    assert!(add_to_watchlist(&kv, "7", "SOL").await.is_err());
+    assert!(remove_from_watchlist(&kv, "7", "BTC").await.is_err());
+    assert_eq!(
+        kv.get_text(&keys::watchlist("7")).await.unwrap().as_deref(),
+        Some(stored)
+    );
+}
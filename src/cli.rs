@@ -0,0 +1,379 @@
+//! Native command line tools behind the `catboard` binary.
+
+use std::{collections::HashMap, env, fs, net::SocketAddr, path::PathBuf, str::FromStr};
+
+use pyth_client::{load_mapping, load_price, load_product};
+
+use crate::{
+    dev_server::{load_env, serve},
+    discord::{command::CommandRegistry, registration::sync_commands},
+    endpoints::Endpoints,
+    error::Error,
+    kv::{bindings::Bindings, keys},
+    pyth::adaptor::{get_product_symbol, get_pyth_mapping_account},
+    solana::{
+        fixture::{fetch_account_fixture, AccountFixture},
+        pubkey::Pubkey,
+        web3::Cluster,
+    },
+};
+
+const USAGE: &str = "Usage:
+  catboard register-commands [--guild <GUILD_ID>] [--dry-run]
+  catboard record-fixtures [--cluster <CLUSTER>] [--out <DIR>] [--pyth <SYMBOLS>] [<PUBKEY>[=<NAME>]...]
+  catboard dev [--port <PORT>] [--env <FILE>] [--kv-dir <DIR>] [--symbols <SYMBOLS>]
+
+Options of record-fixtures:
+  --cluster  devnet, testnet, mainnet-beta or a JSON-RPC url, defaults to devnet.
+  --out      Directory to write <NAME>.json fixtures to, defaults to tests/fixtures.
+             Accounts are named <PUBKEY>.json unless given a name.
+  --pyth     Comma separated Pyth symbols like Crypto.SOL/USD, recorded with the mapping
+             and every product account it lists, as mapping.json, sol_usd_product.json
+             and sol_usd_price.json.
+
+Options of dev:
+  --port     Port to listen on, defaults to 8787.
+  --env      .env file or wrangler.toml whose [vars] are the worker env, defaults to .env.
+  --kv-dir   Keep each KV namespace in <DIR>/<BINDING>.json, in memory by default.
+  --symbols  Comma separated pairs to sync, e.g. Crypto.SOL/USD, stored as SYMBOLS.
+
+Environment:
+  DISCORD_APPLICATION_ID  Application to register commands for.
+  DISCORD_TOKEN           Bot token.
+  DISCORD_API_URL         Override the Discord API base URL (optional).";
+
+fn env_var(key: &str) -> Result<String, Error> {
+    env::var(key).map_err(|_| Error::EnvironmentVariableNotFound(key.to_string()))
+}
+
+fn option_value<'a>(
+    args: &mut impl Iterator<Item = &'a String>,
+    option: &str,
+) -> Result<&'a String, Error> {
+    args.next()
+        .ok_or_else(|| Error::InvalidPayload(format!("{} requires a value", option)))
+}
+
+fn parse_pubkey(pubkey: &str) -> Result<Pubkey, Error> {
+    Pubkey::from_str(pubkey)
+        .map_err(|error| Error::InvalidPayload(format!("{} is not a pubkey, {}", pubkey, error)))
+}
+
+/// `<PUBKEY>[=<NAME>]`, named after the pubkey by default.
+fn parse_named_pubkey(arg: &str) -> Result<(Pubkey, String), Error> {
+    match arg.split_once('=') {
+        Some((pubkey, name)) => Ok((parse_pubkey(pubkey)?, name.to_string())),
+        None => Ok((parse_pubkey(arg)?, arg.to_string())),
+    }
+}
+
+/// `Crypto.SOL/USD` is recorded as `sol_usd_product.json` and `sol_usd_price.json`.
+fn pyth_fixture_name(symbol: &str, kind: &str) -> String {
+    let name: String = symbol
+        .rsplit('.')
+        .next()
+        .unwrap_or(symbol)
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c.to_ascii_lowercase(),
+            false => '_',
+        })
+        .collect();
+
+    format!("{}_{}", name, kind)
+}
+
+/// `name`, then `name_2`, `name_3` for the accounts after it.
+fn numbered(name: &str, index: usize) -> String {
+    match index {
+        0 => name.to_string(),
+        _ => format!("{}_{}", name, index + 1),
+    }
+}
+
+pub async fn run(args: Vec<String>) -> i32 {
+    let result = match args.first().map(String::as_str) {
+        Some("register-commands") => register_commands(&args[1..]).await,
+        Some("record-fixtures") => record_fixtures(&args[1..]).await,
+        Some("dev") => dev(&args[1..]).await,
+        _ => {
+            eprintln!("{}", USAGE);
+            return 2;
+        }
+    };
+
+    match result {
+        Ok(_) => 0,
+        Err(error) => {
+            eprintln!("{}", error);
+            1
+        }
+    }
+}
+
+async fn register_commands(args: &[String]) -> Result<(), Error> {
+    let mut guild_id = None;
+    let mut dry_run = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--dry-run" => dry_run = true,
+            "--guild" => guild_id = Some(option_value(&mut args, arg)?.clone()),
+            _ => return Err(Error::InvalidPayload(format!("unknown argument {}", arg))),
+        }
+    }
+
+    let application_id = env_var("DISCORD_APPLICATION_ID")?;
+    let token = env_var("DISCORD_TOKEN")?;
+    let client = Endpoints::from_env(&env::vars().collect()).discord(&token);
+
+    let commands = CommandRegistry::default().definitions();
+    let diff = sync_commands(
+        &client,
+        &application_id,
+        guild_id.as_deref(),
+        &commands,
+        dry_run,
+    )
+    .await?;
+
+    print!("{}", diff);
+    if dry_run && !diff.is_empty() {
+        println!("Dry run, nothing was registered.");
+    }
+
+    Ok(())
+}
+
+async fn record_fixtures(args: &[String]) -> Result<(), Error> {
+    let mut cluster = Cluster::Devnet;
+    let mut out = PathBuf::from("tests/fixtures");
+    let mut symbols = Vec::new();
+    let mut pubkeys = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--cluster" => cluster = Cluster::from_str(option_value(&mut args, arg)?)?,
+            "--out" => out = PathBuf::from(option_value(&mut args, arg)?),
+            "--pyth" => symbols.extend(
+                option_value(&mut args, arg)?
+                    .split(',')
+                    .map(|symbol| symbol.trim().to_string()),
+            ),
+            pubkey if !pubkey.starts_with("--") => pubkeys.push(parse_named_pubkey(pubkey)?),
+            _ => return Err(Error::InvalidPayload(format!("unknown argument {}", arg))),
+        }
+    }
+    if pubkeys.is_empty() && symbols.is_empty() {
+        return Err(Error::InvalidPayload(
+            "nothing to record, pass pubkeys or --pyth".to_string(),
+        ));
+    }
+
+    let mut fixtures = Vec::new();
+    for (pubkey, name) in pubkeys.into_iter() {
+        fixtures.push((name, fetch_account_fixture(&cluster, &pubkey).await?));
+    }
+    if !symbols.is_empty() {
+        fixtures.extend(record_pyth_accounts(&cluster, &symbols).await?);
+    }
+
+    fs::create_dir_all(&out)?;
+    let mut written = Vec::new();
+    for (name, fixture) in fixtures.iter() {
+        // Symbols sharing a name across asset classes fall back to the pubkey
+        let name = match written.contains(name) {
+            true => &fixture.pubkey,
+            false => name,
+        };
+        written.push(name.clone());
+        let path = out.join(format!("{}.json", name));
+        fs::write(&path, serde_json::to_string_pretty(fixture)? + "\n")?;
+        println!("{}", path.display());
+    }
+
+    Ok(())
+}
+
+/// Mapping accounts of `cluster`, every product they list, and the price accounts of `symbols`
+/// along their `next` lists, enough for the adaptor to walk them offline. Each is named after
+/// what it holds.
+async fn record_pyth_accounts(
+    cluster: &Cluster,
+    symbols: &[String],
+) -> Result<Vec<(String, AccountFixture)>, Error> {
+    let mut fixtures = Vec::new();
+    let mut found = Vec::new();
+    let mut akey = parse_pubkey(get_pyth_mapping_account(cluster))?;
+    let mut mapping_index = 0;
+
+    loop {
+        let mapping = fetch_account_fixture(cluster, &akey).await?;
+        let map_data = mapping.data();
+        let map_acct = load_mapping(&map_data).map_err(|_| {
+            Error::InvalidPayload(format!("{} is not a Pyth mapping account", akey))
+        })?;
+        fixtures.push((numbered("mapping", mapping_index), mapping));
+        mapping_index += 1;
+
+        for prod_akey in map_acct.products.iter().take(map_acct.num as usize) {
+            let product =
+                fetch_account_fixture(cluster, &Pubkey::new_from_array(prod_akey.val)).await?;
+            let prod_data = product.data();
+
+            let prod_acct = match load_product(&prod_data) {
+                Ok(prod_acct) => prod_acct,
+                Err(_) => {
+                    fixtures.push((product.pubkey.clone(), product));
+                    continue;
+                }
+            };
+            let symbol = get_product_symbol(prod_acct);
+            fixtures.push((pyth_fixture_name(&symbol, "product"), product));
+            if !symbols.contains(&symbol) || !prod_acct.px_acc.is_valid() {
+                continue;
+            }
+
+            let mut px_pkey = Pubkey::new_from_array(prod_acct.px_acc.val);
+            for index in 0.. {
+                let price = fetch_account_fixture(cluster, &px_pkey).await?;
+                let price_data = price.data();
+                let name = numbered(&pyth_fixture_name(&symbol, "price"), index);
+                fixtures.push((name, price));
+
+                match load_price(&price_data) {
+                    Ok(pa) if pa.next.is_valid() => px_pkey = Pubkey::new_from_array(pa.next.val),
+                    _ => break,
+                }
+            }
+            found.push(symbol);
+        }
+
+        if !map_acct.next.is_valid() {
+            break;
+        }
+        akey = Pubkey::new_from_array(map_acct.next.val);
+    }
+
+    match symbols.iter().find(|symbol| !found.contains(symbol)) {
+        Some(missing) => Err(Error::NotFound(format!("Pyth product {}", missing))),
+        None => Ok(fixtures),
+    }
+}
+
+async fn dev(args: &[String]) -> Result<(), Error> {
+    let mut port = 8787;
+    let mut env_path = None;
+    let mut kv_dir = None;
+    let mut symbols = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--port" => {
+                port = option_value(&mut args, arg)?
+                    .parse()
+                    .map_err(|_| Error::InvalidPayload("--port must be a number".to_string()))?
+            }
+            "--env" => env_path = Some(PathBuf::from(option_value(&mut args, arg)?)),
+            "--kv-dir" => kv_dir = Some(PathBuf::from(option_value(&mut args, arg)?)),
+            "--symbols" => symbols = Some(option_value(&mut args, arg)?.clone()),
+            _ => return Err(Error::InvalidPayload(format!("unknown argument {}", arg))),
+        }
+    }
+
+    // `.env` is optional unless asked for
+    let env = match env_path {
+        Some(path) => load_env(&path)?,
+        None => load_env(".env".as_ref()).unwrap_or_else(|_| HashMap::new()),
+    };
+    let bindings = match kv_dir {
+        Some(dir) => {
+            fs::create_dir_all(&dir)?;
+            Bindings::in_directory(&dir)
+        }
+        None => Bindings::in_memory(),
+    };
+    if let Some(symbols) = symbols {
+        bindings
+            .products(&Cluster::Devnet)
+            .put_text(keys::SYMBOLS, &symbols, 60 * 60 * 24 * 365)
+            .await?;
+    }
+
+    serve(SocketAddr::from(([127, 0, 0, 1], port)), env, bindings).await
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_record_pyth_fixtures() {
+    use crate::testing::{
+        load_fixtures, pyth_devnet_fixtures, pyth_devnet_fixtures_dir, start_rpc_server,
+    };
+
+    let server = start_rpc_server(pyth_devnet_fixtures()).await;
+    let out = env::temp_dir().join(format!("catboard-fixtures-{}", std::process::id()));
+    let args: Vec<String> = [
+        "--cluster",
+        &server.url(),
+        "--out",
+        &out.display().to_string(),
+        "--pyth",
+        "Crypto.BTC/USD,Crypto.ETH/USD,Crypto.SOL/USD",
+        "J83w4HKfqxwcq3BEMMkPFSppX3gqekLyLJBexebFVkix=sol",
+        "EdVCmQ9FSPcVe5YySXDPCRmc8aDQLKJ9xvYBMZPie1Vw",
+    ]
+    .iter()
+    .map(|arg| arg.to_string())
+    .collect();
+
+    record_fixtures(&args).await.unwrap();
+
+    // Written as the tests load them
+    let mut recorded = load_fixtures(&out);
+    let named: Vec<_> = recorded
+        .iter()
+        .map(|(name, fixture)| (name.as_str(), fixture.pubkey.as_str()))
+        .filter(|(name, _)| !name.contains('_') && *name != "mapping")
+        .collect();
+    assert_eq!(
+        named,
+        vec![
+            (
+                "EdVCmQ9FSPcVe5YySXDPCRmc8aDQLKJ9xvYBMZPie1Vw",
+                "EdVCmQ9FSPcVe5YySXDPCRmc8aDQLKJ9xvYBMZPie1Vw"
+            ),
+            ("sol", "J83w4HKfqxwcq3BEMMkPFSppX3gqekLyLJBexebFVkix"),
+        ]
+    );
+    recorded.retain(|(name, _)| name.contains('_') || name == "mapping");
+    assert_eq!(recorded, load_fixtures(&pyth_devnet_fixtures_dir()));
+
+    let missing = [
+        "--cluster".to_string(),
+        server.url(),
+        "--pyth".to_string(),
+        "Crypto.DOGE/USD".to_string(),
+    ];
+    assert!(record_fixtures(&missing).await.is_err());
+
+    fs::remove_dir_all(&out).unwrap();
+}
+
+#[cfg(test)]
+#[test]
+fn test_fixture_names() {
+    assert_eq!(
+        pyth_fixture_name("Crypto.SOL/USD", "price"),
+        "sol_usd_price"
+    );
+    assert_eq!(
+        pyth_fixture_name("Equity.US.AAPL/USD", "product"),
+        "aapl_usd_product"
+    );
+    assert_eq!(numbered("mapping", 0), "mapping");
+    assert_eq!(numbered("sol_usd_price", 1), "sol_usd_price_2");
+    assert!(parse_named_pubkey("nope=state").is_err());
+}
@@ -1,6 +1,13 @@
+use async_trait::async_trait;
 use js_sys::{ArrayBuffer, Object, Reflect, Uint8Array};
+use serde_json::Value;
 use wasm_bindgen::prelude::*;
 
+use crate::{
+    error::Error,
+    kv::{bindings::Bindings, KeyValueStore, ListOptions, ListResult, PutOptions},
+};
+
 #[wasm_bindgen]
 extern "C" {
     pub type WorkersKvJs;
@@ -10,7 +17,7 @@ extern "C" {
         this: &WorkersKvJs,
         k: JsValue,
         v: JsValue,
-        // options: JsValue,
+        options: JsValue,
     ) -> Result<JsValue, JsValue>;
 
     #[wasm_bindgen(structural, method, catch)]
@@ -19,60 +26,131 @@ extern "C" {
         key: JsValue,
         options: JsValue,
     ) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(structural, method, catch, js_name = getWithMetadata)]
+    pub async fn get_with_metadata(
+        this: &WorkersKvJs,
+        key: JsValue,
+        options: JsValue,
+    ) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(structural, method, catch)]
+    pub async fn delete(this: &WorkersKvJs, key: JsValue) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(structural, method, catch)]
+    pub async fn list(this: &WorkersKvJs, options: JsValue) -> Result<JsValue, JsValue>;
+}
+
+/// Object of every KV namespace, named as bound in `wrangler.toml`.
+#[wasm_bindgen]
+extern "C" {
+    pub type BindingsJs;
+
+    #[wasm_bindgen(structural, method, getter, js_name = DEVNET_PYTH_PRODUCTS)]
+    pub fn devnet_pyth_products(this: &BindingsJs) -> WorkersKvJs;
+
+    #[wasm_bindgen(structural, method, getter, js_name = DEVNET_PYTH_PRICES)]
+    pub fn devnet_pyth_prices(this: &BindingsJs) -> WorkersKvJs;
+
+    #[wasm_bindgen(structural, method, getter, js_name = MAINNETBETA_PYTH_PRODUCTS)]
+    pub fn mainnetbeta_pyth_products(this: &BindingsJs) -> WorkersKvJs;
+
+    #[wasm_bindgen(structural, method, getter, js_name = MAINNETBETA_PYTH_PRICES)]
+    pub fn mainnetbeta_pyth_prices(this: &BindingsJs) -> WorkersKvJs;
+
+    #[wasm_bindgen(structural, method, getter, js_name = USERS)]
+    pub fn users(this: &BindingsJs) -> WorkersKvJs;
+}
+
+impl From<BindingsJs> for Bindings {
+    fn from(bindings: BindingsJs) -> Self {
+        let store = |kv: WorkersKvJs| -> Box<dyn KeyValueStore> { Box::new(WorkersKv { kv }) };
+
+        Bindings {
+            devnet_products: store(bindings.devnet_pyth_products()),
+            devnet_prices: store(bindings.devnet_pyth_prices()),
+            mainnet_products: store(bindings.mainnetbeta_pyth_products()),
+            mainnet_prices: store(bindings.mainnetbeta_pyth_prices()),
+            users: store(bindings.users()),
+        }
+    }
 }
 
 pub(crate) struct WorkersKv {
     pub kv: WorkersKvJs,
 }
 
-#[allow(dead_code)]
-impl WorkersKv {
-    pub async fn put_text(&self, key: &str, value: &str, ttl: u64) -> Result<(), JsValue> {
+fn js_error(error: JsValue) -> Error {
+    Error::KvFailed(error.as_string().unwrap_or_else(|| format!("{:?}", error)))
+}
+
+#[async_trait(?Send)]
+impl KeyValueStore for WorkersKv {
+    async fn get_with_metadata(
+        &self,
+        key: &str,
+    ) -> Result<Option<(Vec<u8>, Option<Value>)>, Error> {
         let options = Object::new();
-        Reflect::set(&options, &"expirationTtl".into(), &(ttl as f64).into())?;
-        self.kv
-            .put(JsValue::from_str(key), value.into()) //, options.into())
-            .await?;
-        Ok(())
+        Reflect::set(&options, &"type".into(), &"arrayBuffer".into()).map_err(js_error)?;
+        // { value, metadata }, value is null when the key doesn't exist
+        let result = self
+            .kv
+            .get_with_metadata(JsValue::from_str(key), options.into())
+            .await
+            .map_err(js_error)?;
+        let value = Reflect::get(&result, &"value".into()).map_err(js_error)?;
+        if value.is_null() {
+            return Ok(None);
+        }
+
+        let buffer = ArrayBuffer::from(value);
+        let typed_array = Uint8Array::new_with_byte_offset(&buffer, 0);
+        let mut v = vec![0; typed_array.length() as usize];
+        typed_array.copy_to(v.as_mut_slice());
+
+        let metadata = Reflect::get(&result, &"metadata".into()).map_err(js_error)?;
+        let metadata: Option<Value> = metadata.into_serde()?;
+
+        Ok(Some((v, metadata)))
     }
 
-    pub async fn put_vec(&self, key: &str, value: &[u8], ttl: u64) -> Result<(), JsValue> {
-        let options = Object::new();
-        Reflect::set(&options, &"expirationTtl".into(), &(ttl as f64).into())?;
+    async fn put(&self, key: &str, value: &[u8], mut options: PutOptions) -> Result<(), Error> {
+        // Workers KV takes one of the two
+        if options.expiration.is_some() {
+            options.expiration_ttl = None;
+        }
         let typed_array = Uint8Array::new_with_length(value.len() as u32);
         typed_array.copy_from(value);
         self.kv
             .put(
                 JsValue::from_str(key),
                 typed_array.buffer().into(),
-                // options.into(),
+                JsValue::from_serde(&options)?,
             )
-            .await?;
+            .await
+            .map_err(js_error)?;
         Ok(())
     }
 
-    pub async fn get_text(&self, key: &str) -> Result<Option<String>, JsValue> {
-        let options = Object::new();
-        Reflect::set(&options, &"type".into(), &"text".into())?;
-        Ok(self
-            .kv
-            .get(JsValue::from_str(key), options.into())
-            .await?
-            .as_string())
+    async fn delete(&self, key: &str) -> Result<(), Error> {
+        self.kv
+            .delete(JsValue::from_str(key))
+            .await
+            .map_err(js_error)?;
+        Ok(())
     }
 
-    pub async fn get_vec(&self, key: &str) -> Result<Option<Vec<u8>>, JsValue> {
-        let options = Object::new();
-        Reflect::set(&options, &"type".into(), &"arrayBuffer".into())?;
-        let value = self.kv.get(JsValue::from_str(key), options.into()).await?;
-        if value.is_null() {
-            Ok(None)
-        } else {
-            let buffer = ArrayBuffer::from(value);
-            let typed_array = Uint8Array::new_with_byte_offset(&buffer, 0);
-            let mut v = vec![0; typed_array.length() as usize];
-            typed_array.copy_to(v.as_mut_slice());
-            Ok(Some(v))
-        }
+    async fn list(&self, options: ListOptions) -> Result<ListResult, Error> {
+        let options = ListOptions {
+            limit: Some(options.limit()),
+            ..options
+        };
+        let result = self
+            .kv
+            .list(JsValue::from_serde(&options)?)
+            .await
+            .map_err(js_error)?;
+
+        Ok(result.into_serde()?)
     }
 }
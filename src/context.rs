@@ -1,17 +1,38 @@
 use serde::Deserialize;
-use web_sys::Url;
+use url::Url;
 
 use std::collections::HashMap;
 
 use crate::{
-    catboard::publisher::{fetch_pyth_prices_and_record, fetch_pyth_product_and_record},
-    cloudflare::worker::WorkersKv,
-    discord::{interaction::Interaction, verification::verify_signature},
+    catboard::{
+        chart::{parse_range, render_symbol_chart, ChartStyle},
+        migration::migrate_devnet_products,
+        publisher::{fetch_pyth_prices_and_record, fetch_pyth_product_and_record},
+    },
+    discord::{
+        interaction::Interaction,
+        verification::{verify_api_key, verify_signature},
+    },
+    endpoints::Endpoints,
     error::Error,
     http::{HttpError, HttpRequest, HttpResponse},
+    kv::{bindings::Bindings, keys},
     solana::web3::Cluster,
+    task::Task,
 };
 
+/// `/api` functions that rewrite stored records, only run with the `x-api-key` set as `API_KEY`.
+const API_KEY_FUNCTIONS: [&str; 1] = ["migrate_kv"];
+
+/// Pairs to sync, as configured at `SYMBOLS`.
+async fn get_synced_symbols(bindings: &Bindings) -> Result<String, Error> {
+    bindings
+        .products(&Cluster::Devnet)
+        .get_text(keys::SYMBOLS)
+        .await?
+        .ok_or_else(|| Error::NotFound(format!("{} to sync", keys::SYMBOLS)))
+}
+
 #[derive(Deserialize)]
 pub(crate) struct Context {
     pub(crate) env: HashMap<String, String>,
@@ -25,6 +46,24 @@ impl Context {
             .ok_or_else(|| Error::EnvironmentVariableNotFound(key.to_string()))
     }
 
+    fn endpoints(&self) -> Endpoints {
+        Endpoints::from_env(&self.env)
+    }
+
+    fn url(&self) -> Result<Url, Error> {
+        Url::parse(&self.request.url)
+            .map_err(|_| Error::InvalidPayload(format!("url {}", self.request.url)))
+    }
+
+    /// Value of `key` in the query string, e.g. `30d` for `?range=30d`.
+    fn query(&self, key: &str) -> Option<String> {
+        self.url()
+            .ok()?
+            .query_pairs()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.into_owned())
+    }
+
     fn perform_verification(&self) -> Result<(), Error> {
         let public_key = self.env("PUBLIC_KEY")?;
         let signature = self.request.header("x-signature-ed25519")?;
@@ -34,138 +73,309 @@ impl Context {
             .map_err(Error::VerificationFailed)
     }
 
-    #[allow(dead_code)]
-    fn perform_api_verification(&self, _request_api_key: &str) -> Result<(), Error> {
-        // Will temporary use PUBLIC_KEY as API_KEY
-        // TODO: verify with signature
-        // let api_key = self.env("PUBLIC_KEY").unwrap();
+    /// Unverifiable until the `API_KEY` secret is set.
+    fn perform_api_verification(&self, request_api_key: &str) -> Result<(), Error> {
+        let api_key = self.env("API_KEY")?;
 
-        // verify_api_key(request_api_key, api_key).map_err(Error::VerificationFailed)
-        Ok(())
+        verify_api_key(request_api_key, api_key).map_err(Error::VerificationFailed)
     }
 
-    async fn handle_payload(&self, kv: &WorkersKv) -> Result<String, Error> {
+    async fn handle_payload(&self, bindings: &Bindings) -> Result<(String, Vec<Task>), Error> {
         let payload = &self.request.body;
         let interaction =
             serde_json::from_str::<Interaction>(payload).map_err(Error::JsonFailed)?;
-        let response = interaction.perform(kv).await;
+        let mut response = interaction.perform(bindings, &self.endpoints()).await?;
+        let tasks = std::mem::take(&mut response.tasks);
 
-        serde_json::to_string(&response.unwrap()).map_err(Error::JsonFailed)
+        Ok((
+            serde_json::to_string(&response).map_err(Error::JsonFailed)?,
+            tasks,
+        ))
     }
 
-    async fn handle_api_payload(&self, kv: &WorkersKv, fn_name: &str) -> Result<String, Error> {
-        // TODO : test this
-        let symbols = kv.get_text("SYMBOLS").await.unwrap().unwrap();
-        let symbols = symbols.split(",").collect();
+    async fn handle_api_payload(
+        &self,
+        bindings: &Bindings,
+        fn_name: &str,
+    ) -> Result<String, Error> {
+        let endpoints = self.endpoints();
+        // Alerts are posted as the bot, skip posting when no token is configured
+        let notifier = self
+            .env("DISCORD_TOKEN")
+            .ok()
+            .map(|token| endpoints.discord(token));
 
         let response = match fn_name {
-            "sync_products" => fetch_pyth_product_and_record(kv, &Cluster::Devnet, symbols).await,
-            "sync_prices" => fetch_pyth_prices_and_record(kv, &Cluster::Devnet, symbols).await,
-            _ => todo!(), // _ => Ok(hello().await).map_err(Error::JsonFailed),
-        };
+            "sync_products" => {
+                let symbols = get_synced_symbols(bindings).await?;
+                fetch_pyth_product_and_record(
+                    bindings,
+                    &endpoints,
+                    &Cluster::Devnet,
+                    symbols.split(',').collect(),
+                )
+                .await
+            }
+            "sync_prices" => {
+                let symbols = get_synced_symbols(bindings).await?;
+                fetch_pyth_prices_and_record(
+                    bindings,
+                    &endpoints,
+                    &Cluster::Devnet,
+                    symbols.split(',').collect(),
+                    notifier.as_ref(),
+                )
+                .await
+            }
+            "migrate_kv" => migrate_devnet_products(bindings).await,
+            _ => Err(Error::NotFound(format!("API {}", fn_name))),
+        }?;
 
-        serde_json::to_string(&response.unwrap()).map_err(Error::JsonFailed)
+        serde_json::to_string(&response).map_err(Error::JsonFailed)
     }
 
-    pub(crate) async fn handle_signed_http_request(&self, kv: &WorkersKv) -> HttpResponse {
+    pub(crate) async fn handle_signed_http_request(&self, bindings: &Bindings) -> HttpResponse {
         let verified_result = self.perform_verification().map_err(HttpError::from);
         match verified_result {
             Ok(_) => {
-                let result = self.handle_payload(kv).await.map_err(HttpError::from);
+                let result = self.handle_payload(bindings).await.map_err(HttpError::from);
 
                 match result {
-                    Ok(body) => HttpResponse { status: 200, body },
+                    Ok((body, tasks)) => HttpResponse {
+                        status: 200,
+                        body,
+                        tasks,
+                        ..Default::default()
+                    },
                     Err(error) => HttpResponse {
                         body: error.to_string(),
                         status: error.status as u16,
+                        ..Default::default()
                     },
                 }
             }
             Err(error) => HttpResponse {
                 body: error.to_string(),
                 status: error.status as u16,
+                ..Default::default()
             },
         }
     }
 
-    #[allow(dead_code)]
     pub(crate) async fn handle_api_http_request(
         &self,
-        kv: &WorkersKv,
+        bindings: &Bindings,
         fn_name: &str,
     ) -> HttpResponse {
-        let request_api_key = self.request.header("x-api-key");
-
         let verified_result = self
-            .perform_api_verification(request_api_key.unwrap())
+            .request
+            .header("x-api-key")
+            .and_then(|request_api_key| self.perform_api_verification(request_api_key))
             .map_err(HttpError::from);
 
         match verified_result {
             Ok(_) => {
                 let result = self
-                    .handle_api_payload(kv, fn_name)
+                    .handle_api_payload(bindings, fn_name)
                     .await
                     .map_err(HttpError::from);
 
                 match result {
-                    Ok(body) => HttpResponse { status: 200, body },
+                    Ok(body) => HttpResponse {
+                        status: 200,
+                        body,
+                        ..Default::default()
+                    },
                     Err(error) => HttpResponse {
                         body: error.to_string(),
                         status: error.status as u16,
+                        ..Default::default()
                     },
                 }
             }
             Err(error) => HttpResponse {
                 body: error.to_string(),
                 status: error.status as u16,
+                ..Default::default()
             },
         }
     }
 
     pub(crate) async fn handle_internal_http_request(
         &self,
-        kv: &WorkersKv,
+        bindings: &Bindings,
         fn_name: &str,
     ) -> HttpResponse {
         let result = self
-            .handle_api_payload(kv, fn_name)
+            .handle_api_payload(bindings, fn_name)
+            .await
+            .map_err(HttpError::from);
+
+        match result {
+            Ok(body) => HttpResponse {
+                status: 200,
+                body,
+                ..Default::default()
+            },
+            Err(error) => HttpResponse {
+                body: error.to_string(),
+                status: error.status as u16,
+                ..Default::default()
+            },
+        }
+    }
+
+    async fn handle_chart_payload(
+        &self,
+        bindings: &Bindings,
+        file_name: &str,
+    ) -> Result<Vec<u8>, Error> {
+        // file_name = SOL.png, ?range=30d&style=line
+        let symbol = file_name.trim_end_matches(".png");
+        let days = parse_range(&self.query("range").unwrap_or_else(|| "30d".to_string()))
+            .ok_or_else(|| Error::InvalidPayload("range".to_string()))?;
+        let style =
+            ChartStyle::from_option(&self.query("style").unwrap_or_else(|| "line".to_string()))
+                .ok_or_else(|| Error::InvalidPayload("style".to_string()))?;
+
+        render_symbol_chart(bindings.prices(&Cluster::Devnet), symbol, days, style)
+            .await
+            .ok_or_else(|| Error::NotFound(format!("price history of {}", symbol)))
+    }
+
+    pub(crate) async fn handle_chart_http_request(
+        &self,
+        bindings: &Bindings,
+        file_name: &str,
+    ) -> HttpResponse {
+        let result = self
+            .handle_chart_payload(bindings, file_name)
             .await
             .map_err(HttpError::from);
 
         match result {
-            Ok(body) => HttpResponse { status: 200, body },
+            Ok(bytes) => HttpResponse {
+                status: 200,
+                bytes: Some(bytes),
+                content_type: Some("image/png".to_string()),
+                ..Default::default()
+            },
             Err(error) => HttpResponse {
                 body: error.to_string(),
                 status: error.status as u16,
+                ..Default::default()
             },
         }
     }
 
-    pub(crate) async fn handle_http_request(&self, kv: &WorkersKv) -> HttpResponse {
-        let url = Url::new(&self.request.url).unwrap();
-        let pathname = url.pathname();
-        let pathname_str = pathname.as_str();
+    pub(crate) async fn handle_http_request(&self, bindings: &Bindings) -> HttpResponse {
+        let url = match self.url() {
+            Ok(url) => url,
+            Err(error) => {
+                let error = HttpError::from(error);
+                return HttpResponse {
+                    body: error.to_string(),
+                    status: error.status as u16,
+                    ..Default::default()
+                };
+            }
+        };
+        let pathname_str = url.path();
 
-        // pathname = /api/sync_prices
+        // pathname = /api/sync_prices, /api/chart/SOL.png
         let paths: Vec<&str> = pathname_str.split('/').collect();
-        let response = match paths[1] {
-            "api" => self.handle_internal_http_request(kv, paths[2]).await,
-            _ => self.handle_signed_http_request(kv).await,
+        let response = match paths[1..] {
+            ["api", "chart", file_name] => {
+                self.handle_chart_http_request(bindings, file_name).await
+            }
+            ["api", fn_name, ..] if API_KEY_FUNCTIONS.contains(&fn_name) => {
+                self.handle_api_http_request(bindings, fn_name).await
+            }
+            ["api", fn_name, ..] => self.handle_internal_http_request(bindings, fn_name).await,
+            _ => self.handle_signed_http_request(bindings).await,
         };
 
         response
 
         // let request_api_key = self.request.header("x-api-key").map_err(|_|=>Err(());
         // if let None = Some(request_api_key) {
-        //     self.handle_signed_http_request(kv).await
+        //     self.handle_signed_http_request(bindings).await
         // } else {
-        //     self.handle_api_http_request(kv).await
+        //     self.handle_api_http_request(bindings).await
         // }
         // if request_api_key.len() > 0 {
-        //     return self.handle_api_http_request(kv).await;
+        //     return self.handle_api_http_request(bindings).await;
         // } else {
-        //     return self.handle_signed_http_request(kv).await;
+        //     return self.handle_signed_http_request(bindings).await;
         // }
-        // return self.handle_signed_http_request(kv).await;
+        // return self.handle_signed_http_request(bindings).await;
+    }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_api_not_found() {
+    let bindings = Bindings::in_memory();
+    let context = |path: &str| Context {
+        env: HashMap::new(),
+        request: HttpRequest {
+            method: "GET".to_string(),
+            url: format!("https://catboard.workers.dev{}", path),
+            headers: HashMap::new(),
+            body: String::new(),
+        },
+    };
+
+    let response = context("/api/nope").handle_http_request(&bindings).await;
+    assert_eq!(response.status, 404);
+    assert_eq!(
+        response.body,
+        "An HTTP error occurred: Not found: API nope."
+    );
+
+    // Nothing configured to sync yet
+    let response = context("/api/sync_prices")
+        .handle_http_request(&bindings)
+        .await;
+    assert_eq!(response.status, 404);
+    assert_eq!(
+        response.body,
+        "An HTTP error occurred: Not found: SYMBOLS to sync."
+    );
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_migrate_kv_requires_api_key() {
+    let bindings = Bindings::in_memory();
+    let context = |api_key: Option<&str>, header: Option<&str>| Context {
+        env: api_key
+            .map(|api_key| ("API_KEY".to_string(), api_key.to_string()))
+            .into_iter()
+            .collect(),
+        request: HttpRequest {
+            method: "POST".to_string(),
+            url: "https://catboard.workers.dev/api/migrate_kv/".to_string(),
+            headers: header
+                .map(|header| ("x-api-key".to_string(), header.to_string()))
+                .into_iter()
+                .collect(),
+            body: String::new(),
+        },
+    };
+
+    for (api_key, header, status) in [
+        (Some("secret"), None, 400),
+        (Some("secret"), Some("secreT"), 401),
+        (Some("secret"), Some("secret2"), 401),
+        // Not configured, nobody can run it
+        (None, Some(""), 500),
+        (Some(""), Some(""), 401),
+        (Some("secret"), Some("secret"), 200),
+    ] {
+        let response = context(api_key, header)
+            .handle_http_request(&bindings)
+            .await;
+        assert_eq!(response.status, status, "{:?} {:?}", api_key, header);
     }
 }
@@ -0,0 +1,238 @@
+//! Local HTTP server running the same handlers as `wasm_main`, without wrangler.
+
+use std::{
+    collections::HashMap, convert::Infallible, fs, mem, net::SocketAddr, path::Path, rc::Rc,
+};
+
+use hyper::{
+    header::{CONTENT_TYPE, HOST},
+    service::{make_service_fn, service_fn},
+    Body, Request, Response, Server, StatusCode,
+};
+use tokio::{
+    sync::{mpsc, oneshot},
+    task::{spawn_local, LocalSet},
+};
+
+use crate::{
+    context::Context,
+    error::Error,
+    http::{HttpRequest, HttpResponse},
+    kv::bindings::Bindings,
+    task::run_tasks,
+};
+
+/// Request to handle and where to send its response.
+type Job = (Context, oneshot::Sender<Response<Body>>);
+
+/// `KEY=value` lines of a `.env` file, skipping blanks and `#` comments.
+fn parse_dotenv(text: &str) -> HashMap<String, String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.strip_prefix("export ").unwrap_or(line).split_once('='))
+        .map(|(key, value)| {
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+                .unwrap_or(value);
+            (key.trim().to_string(), value.to_string())
+        })
+        .collect()
+}
+
+/// `[vars]` of a `wrangler.toml`.
+fn parse_wrangler_vars(text: &str) -> Result<HashMap<String, String>, Error> {
+    let config: toml::Value = toml::from_str(text)
+        .map_err(|error| Error::InvalidPayload(format!("wrangler.toml, {}", error)))?;
+
+    Ok(config
+        .get("vars")
+        .and_then(toml::Value::as_table)
+        .map(|vars| {
+            vars.iter()
+                .filter_map(|(key, value)| Some((key.clone(), value.as_str()?.to_string())))
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Env the worker would see, from a `.toml` file's `[vars]` or a `.env` file.
+pub(crate) fn load_env(path: &Path) -> Result<HashMap<String, String>, Error> {
+    let text = fs::read_to_string(path)?;
+
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("toml") => parse_wrangler_vars(&text),
+        _ => Ok(parse_dotenv(&text)),
+    }
+}
+
+/// Context of an incoming request, as `worker.js` builds it.
+async fn to_context(
+    request: Request<Body>,
+    env: HashMap<String, String>,
+) -> Result<Context, hyper::Error> {
+    let (parts, body) = request.into_parts();
+    let body = hyper::body::to_bytes(body).await?;
+    let host = parts
+        .headers
+        .get(HOST)
+        .and_then(|host| host.to_str().ok())
+        .unwrap_or("localhost");
+    let path = parts
+        .uri
+        .path_and_query()
+        .map(|path| path.as_str())
+        .unwrap_or("/");
+
+    Ok(Context {
+        env,
+        request: HttpRequest {
+            method: parts.method.to_string(),
+            url: format!("http://{}{}", host, path),
+            headers: parts
+                .headers
+                .iter()
+                .map(|(key, value)| {
+                    (
+                        key.to_string(),
+                        value.to_str().unwrap_or_default().to_string(),
+                    )
+                })
+                .collect(),
+            body: String::from_utf8_lossy(&body).to_string(),
+        },
+    })
+}
+
+fn to_response(response: HttpResponse) -> Response<Body> {
+    let content_type = response
+        .content_type
+        .unwrap_or_else(|| "application/json".to_string());
+    let body = match response.bytes {
+        Some(bytes) => Body::from(bytes),
+        None => Body::from(response.body),
+    };
+
+    Response::builder()
+        .status(response.status)
+        .header(CONTENT_TYPE, content_type)
+        .body(body)
+        .unwrap()
+}
+
+/// Pass a request to the handlers and wait for their response.
+async fn handle(
+    request: Request<Body>,
+    env: HashMap<String, String>,
+    jobs: mpsc::UnboundedSender<Job>,
+) -> Result<Response<Body>, hyper::Error> {
+    let context = to_context(request, env).await?;
+    let (reply, response) = oneshot::channel();
+    jobs.send((context, reply)).ok();
+
+    Ok(match response.await {
+        Ok(response) => response,
+        // The handler panicked, it's logged on stderr
+        Err(_) => Response::builder()
+            .status(StatusCode::INTERNAL_SERVER_ERROR)
+            .body(Body::from("handler panicked"))
+            .unwrap(),
+    })
+}
+
+/// Handle every job on this thread, the handlers aren't `Send`. Like `waitUntil`, background
+/// tasks such as follow-ups are finished after the response is sent.
+async fn run_jobs(mut jobs: mpsc::UnboundedReceiver<Job>, bindings: Bindings) {
+    let bindings = Rc::new(bindings);
+
+    while let Some((context, reply)) = jobs.recv().await {
+        let bindings = bindings.clone();
+        spawn_local(async move {
+            let mut response = context.handle_http_request(&bindings).await;
+            let tasks = mem::take(&mut response.tasks);
+            reply.send(to_response(response)).ok();
+
+            if let Err(error) = run_tasks(tasks, bindings).await {
+                eprintln!("Background task failed: {}", error);
+            }
+        });
+    }
+}
+
+/// Serve on `addr` until the process stops.
+pub(crate) async fn serve(
+    addr: SocketAddr,
+    env: HashMap<String, String>,
+    bindings: Bindings,
+) -> Result<(), Error> {
+    let (jobs, queue) = mpsc::unbounded_channel::<Job>();
+    let make_service = make_service_fn(move |_| {
+        let env = env.clone();
+        let jobs = jobs.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                handle(request, env.clone(), jobs.clone())
+            }))
+        }
+    });
+
+    let server = Server::try_bind(&addr)
+        .map_err(|error| Error::ServerFailed(format!("can't bind {}, {}", addr, error)))?
+        .serve(make_service);
+    println!("Listening on http://{}", server.local_addr());
+
+    LocalSet::new()
+        .run_until(async move {
+            spawn_local(run_jobs(queue, bindings));
+            server.await
+        })
+        .await
+        .map_err(|error| Error::ServerFailed(error.to_string()))
+}
+
+#[cfg(test)]
+#[test]
+fn test_parse_env_files() {
+    let env =
+        parse_dotenv("# Discord\nPUBLIC_KEY=abc123\nexport DISCORD_TOKEN=\"Bot xyz\"\n\nBROKEN\n");
+    assert_eq!(env.len(), 2);
+    assert_eq!(env["PUBLIC_KEY"], "abc123");
+    assert_eq!(env["DISCORD_TOKEN"], "Bot xyz");
+
+    let vars = parse_wrangler_vars(include_str!("../wrangler.example.toml")).unwrap();
+    assert_eq!(vars["PUBLIC_KEY"], "...");
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_handle_through_jobs() {
+    let (jobs, queue) = mpsc::unbounded_channel::<Job>();
+    let env: HashMap<String, String> = [("PUBLIC_KEY".to_string(), "00".repeat(32))].into();
+
+    LocalSet::new()
+        .run_until(async move {
+            spawn_local(run_jobs(queue, Bindings::in_memory()));
+
+            // No price history yet
+            let request = Request::get("/api/chart/SOL.png?range=7d")
+                .header(HOST, "localhost:8787")
+                .body(Body::empty())
+                .unwrap();
+            let response = handle(request, env.clone(), jobs.clone()).await.unwrap();
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+            // Unsigned interaction
+            let request = Request::post("/")
+                .body(Body::from(r#"{"type":1}"#))
+                .unwrap();
+            let response = handle(request, env, jobs).await.unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+            assert_eq!(
+                response.headers()[CONTENT_TYPE],
+                "application/json".to_string()
+            );
+        })
+        .await;
+}
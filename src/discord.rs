@@ -1,3 +1,6 @@
-mod command;
+pub(crate) mod command;
 pub(crate) mod interaction;
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) mod registration;
+pub(crate) mod rest;
 pub(crate) mod verification;
@@ -1,29 +1,384 @@
+mod about;
+mod alert;
+mod chart;
+mod convert;
+pub(crate) mod definition;
 mod hello;
+mod help;
+mod indicators;
+mod lend;
 mod price;
+mod stake;
+mod swap_quote;
+mod wallet;
+mod watchlist;
+
+use async_trait::async_trait;
 
 use crate::{
-    cloudflare::worker::WorkersKv,
     discord::interaction::{
-        ApplicationCommandInteractionData, InteractionResponse, InteractionResponseType,
+        ApplicationCommandInteractionData, ApplicationCommandInteractionDataOptions,
+        ApplicationCommandOptionType, Interaction, InteractionResponse,
+        MessageComponentInteractionData, MessageFlags, ModalSubmitInteractionData,
     },
+    endpoints::Endpoints,
+    kv::bindings::Bindings,
 };
 
+use definition::{ApplicationCommand, ApplicationCommandOption};
+
+/// What a command handler gets to know about its invocation.
+pub(crate) struct CommandContext<'a> {
+    pub(crate) bindings: &'a Bindings,
+    /// Solana RPC, Discord and Jupiter to call.
+    pub(crate) endpoints: &'a Endpoints,
+    pub(crate) interaction: &'a Interaction,
+}
+
+#[async_trait(?Send)]
+pub(crate) trait SlashCommand {
+    fn name(&self) -> &'static str;
+
+    fn description(&self) -> &'static str;
+
+    fn options(&self) -> Vec<ApplicationCommandOption> {
+        Vec::new()
+    }
+
+    /// Example invocations shown by `/help`.
+    fn examples(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+
+    /// Visibility of successful replies when the invoker doesn't pick one.
+    fn visibility(&self) -> Visibility {
+        Visibility::Public
+    }
+
+    async fn handle(
+        &self,
+        ctx: &CommandContext<'_>,
+        options: &CommandOptions<'_>,
+    ) -> InteractionResponse;
+
+    /// Handle a click on a component this command sent, `action` is its custom id without the command prefix.
+    async fn handle_component(
+        &self,
+        _ctx: &CommandContext<'_>,
+        _action: &str,
+        data: &MessageComponentInteractionData,
+    ) -> InteractionResponse {
+        unhandled(&data.custom_id)
+    }
+
+    /// Handle a submit of a modal this command opened, `action` is its custom id without the command prefix.
+    async fn handle_modal_submit(
+        &self,
+        _ctx: &CommandContext<'_>,
+        _action: &str,
+        data: &ModalSubmitInteractionData,
+    ) -> InteractionResponse {
+        unhandled(&data.custom_id)
+    }
+
+    fn definition(&self) -> ApplicationCommand {
+        let mut options = self.options();
+
+        // Discord doesn't allow mixing sub commands with plain options.
+        if !options
+            .iter()
+            .any(|option| option.ty == ApplicationCommandOptionType::SubCommand)
+        {
+            options.push(
+                ApplicationCommandOption::string(VISIBILITY_OPTION, "Who can see the reply.")
+                    .choice("public", "public")
+                    .choice("only me", "ephemeral"),
+            );
+        }
+
+        ApplicationCommand {
+            name: self.name().to_string(),
+            description: self.description().to_string(),
+            options,
+        }
+    }
+}
+
+pub(crate) const VISIBILITY_OPTION: &str = "visibility";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Visibility {
+    Public,
+    Ephemeral,
+}
+
+impl Visibility {
+    fn from_option(value: &str) -> Option<Visibility> {
+        match value {
+            "public" => Some(Visibility::Public),
+            "ephemeral" => Some(Visibility::Ephemeral),
+            _ => None,
+        }
+    }
+}
+
+fn unhandled(name: &str) -> InteractionResponse {
+    InteractionResponse::ephemeral(format!(
+        "😱 Sorry! `{}` is not support. Try `/help` to see what I can do.",
+        name
+    ))
+}
+
+/// Build a component custom id owned by `command`, e.g. `price:alert:SOL`.
+pub(crate) fn custom_id(command: &str, action: &str) -> String {
+    format!("{}:{}", command, action)
+}
+
+fn split_custom_id(custom_id: &str) -> (&str, &str) {
+    match custom_id.split_once(':') {
+        Some((command, action)) => (command, action),
+        None => (custom_id, ""),
+    }
+}
+
+/// Options of an invoked command, looked up by name.
+pub(crate) struct CommandOptions<'a> {
+    options: &'a [ApplicationCommandInteractionDataOptions],
+}
+
+#[allow(dead_code)]
+impl<'a> CommandOptions<'a> {
+    pub(crate) fn new(options: &'a [ApplicationCommandInteractionDataOptions]) -> Self {
+        CommandOptions { options }
+    }
+
+    pub(crate) fn from_data(data: &'a ApplicationCommandInteractionData) -> Self {
+        Self::new(data.options.as_deref().unwrap_or_default())
+    }
+
+    pub(crate) fn get(&self, name: &str) -> Option<&'a serde_json::Value> {
+        self.options
+            .iter()
+            .find(|option| option.name == name)
+            .and_then(|option| option.value.as_ref())
+    }
+
+    pub(crate) fn string(&self, name: &str) -> Option<&'a str> {
+        self.get(name).and_then(|value| value.as_str())
+    }
+
+    pub(crate) fn number(&self, name: &str) -> Option<f64> {
+        self.get(name).and_then(|value| value.as_f64())
+    }
+
+    pub(crate) fn integer(&self, name: &str) -> Option<i64> {
+        self.get(name).and_then(|value| value.as_i64())
+    }
+
+    pub(crate) fn boolean(&self, name: &str) -> Option<bool> {
+        self.get(name).and_then(|value| value.as_bool())
+    }
+
+    /// Invoked sub command name and its own options.
+    pub(crate) fn sub_command(&self) -> Option<(&'a str, CommandOptions<'a>)> {
+        self.options
+            .iter()
+            .find(|option| option.ty == ApplicationCommandOptionType::SubCommand)
+            .map(|option| {
+                (
+                    option.name.as_str(),
+                    CommandOptions::new(option.options.as_deref().unwrap_or_default()),
+                )
+            })
+    }
+}
+
+pub(crate) struct CommandRegistry {
+    commands: Vec<Box<dyn SlashCommand>>,
+}
+
+impl Default for CommandRegistry {
+    fn default() -> Self {
+        CommandRegistry {
+            commands: vec![
+                Box::new(hello::Hello),
+                Box::new(price::Price),
+                Box::new(chart::Chart),
+                Box::new(indicators::Indicators),
+                Box::new(convert::Convert),
+                Box::new(alert::Alert),
+                Box::new(watchlist::Watchlist),
+                Box::new(swap_quote::SwapQuote),
+                Box::new(stake::Stake),
+                Box::new(lend::Lend),
+                Box::new(wallet::Wallet),
+                Box::new(help::Help),
+                Box::new(about::About),
+            ],
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl CommandRegistry {
+    pub(crate) fn commands(&self) -> impl Iterator<Item = &dyn SlashCommand> {
+        self.commands.iter().map(|command| command.as_ref())
+    }
+
+    pub(crate) fn get(&self, name: &str) -> Option<&dyn SlashCommand> {
+        self.commands().find(|command| command.name() == name)
+    }
+
+    pub(crate) fn definitions(&self) -> Vec<ApplicationCommand> {
+        self.commands()
+            .map(|command| command.definition())
+            .collect()
+    }
+
+    pub(crate) async fn dispatch(
+        &self,
+        ctx: &CommandContext<'_>,
+        data: &ApplicationCommandInteractionData,
+    ) -> InteractionResponse {
+        match self.get(data.name.as_str()) {
+            Some(command) => {
+                let options = CommandOptions::from_data(data);
+                let visibility = options
+                    .string(VISIBILITY_OPTION)
+                    .and_then(Visibility::from_option)
+                    .unwrap_or_else(|| command.visibility());
+
+                let response = command.handle(ctx, &options).await;
+                match visibility {
+                    Visibility::Ephemeral => response.with_flags(MessageFlags::EPHEMERAL),
+                    Visibility::Public => response,
+                }
+            }
+            None => unhandled(&format!("/{}", data.name)),
+        }
+    }
+
+    pub(crate) async fn dispatch_component(
+        &self,
+        ctx: &CommandContext<'_>,
+        data: &MessageComponentInteractionData,
+    ) -> InteractionResponse {
+        let (name, action) = split_custom_id(&data.custom_id);
+        match self.get(name) {
+            Some(command) => command.handle_component(ctx, action, data).await,
+            None => unhandled(&data.custom_id),
+        }
+    }
+
+    pub(crate) async fn dispatch_modal_submit(
+        &self,
+        ctx: &CommandContext<'_>,
+        data: &ModalSubmitInteractionData,
+    ) -> InteractionResponse {
+        let (name, action) = split_custom_id(&data.custom_id);
+        match self.get(name) {
+            Some(command) => command.handle_modal_submit(ctx, action, data).await,
+            None => unhandled(&data.custom_id),
+        }
+    }
+}
+
 pub(crate) async fn handle_command(
+    ctx: &CommandContext<'_>,
     data: &ApplicationCommandInteractionData,
-    kv: &WorkersKv,
 ) -> InteractionResponse {
-    match data.name.as_str() {
-        "hello" => hello::hello().await,
-        "price" => {
-            // Params?
-            let options = data.options.as_ref().unwrap();
-            let params = options[0].value.as_str();
-
-            price::price(kv, params).await
-        }
-        _ => InteractionResponse {
-            ty: InteractionResponseType::ACKWithSource,
-            data: None,
-        },
+    CommandRegistry::default().dispatch(ctx, data).await
+}
+
+pub(crate) async fn handle_component(
+    ctx: &CommandContext<'_>,
+    data: &MessageComponentInteractionData,
+) -> InteractionResponse {
+    CommandRegistry::default()
+        .dispatch_component(ctx, data)
+        .await
+}
+
+pub(crate) async fn handle_modal_submit(
+    ctx: &CommandContext<'_>,
+    data: &ModalSubmitInteractionData,
+) -> InteractionResponse {
+    CommandRegistry::default()
+        .dispatch_modal_submit(ctx, data)
+        .await
+}
+
+#[cfg(test)]
+#[test]
+fn test_definitions_match_dispatch_names() {
+    let registry = CommandRegistry::default();
+    let definitions = registry.definitions();
+
+    let names: Vec<&str> = definitions.iter().map(|d| d.name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec![
+            "hello",
+            "price",
+            "chart",
+            "indicators",
+            "convert",
+            "alert",
+            "watchlist",
+            "swap_quote",
+            "stake",
+            "lend",
+            "wallet",
+            "help",
+            "about"
+        ]
+    );
+    for definition in definitions.iter() {
+        assert!(registry.get(&definition.name).is_some());
     }
 }
+
+#[cfg(test)]
+#[test]
+fn test_definitions_serialize_for_discord() {
+    let definitions = CommandRegistry::default().definitions();
+    let json = serde_json::to_value(&definitions).unwrap();
+
+    assert_eq!(
+        json[1],
+        serde_json::json!({
+            "name": "price",
+            "description": "Get the latest price of a symbol from Pyth.",
+            "options": [{
+                "type": 3,
+                "name": "symbol",
+                "description": "Symbol to look up, e.g. SOL.",
+                "required": true,
+            }, {
+                "type": 3,
+                "name": "visibility",
+                "description": "Who can see the reply.",
+                "choices": [
+                    { "name": "public", "value": "public" },
+                    { "name": "only me", "value": "ephemeral" },
+                ],
+            }],
+        })
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_command_options_sub_command() {
+    let options: Vec<ApplicationCommandInteractionDataOptions> = serde_json::from_str(
+        r#"[{"name":"add","type":1,"options":[{"name":"symbol","type":3,"value":"SOL"},{"name":"price","type":10,"value":101.5}]}]"#,
+    )
+    .unwrap();
+    let options = CommandOptions::new(&options);
+
+    let (name, sub_options) = options.sub_command().unwrap();
+    assert_eq!(name, "add");
+    assert_eq!(sub_options.string("symbol"), Some("SOL"));
+    assert_eq!(sub_options.number("price"), Some(101.5));
+    assert_eq!(sub_options.string("missing"), None);
+}
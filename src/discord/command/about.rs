@@ -0,0 +1,45 @@
+use async_trait::async_trait;
+
+use crate::{
+    catboard::consumer::{get_last_synced_at_from_kv, get_symbols_from_kv},
+    discord::{
+        command::{CommandContext, CommandOptions, SlashCommand},
+        interaction::InteractionResponse,
+    },
+    solana::web3::Cluster,
+};
+
+pub(crate) struct About;
+
+#[async_trait(?Send)]
+impl SlashCommand for About {
+    fn name(&self) -> &'static str {
+        "about"
+    }
+
+    fn description(&self) -> &'static str {
+        "Show bot version, cluster and sync status."
+    }
+
+    async fn handle(
+        &self,
+        ctx: &CommandContext<'_>,
+        _options: &CommandOptions<'_>,
+    ) -> InteractionResponse {
+        let symbols = get_symbols_from_kv(ctx.bindings.products(&Cluster::Devnet)).await;
+        let last_synced_at = get_last_synced_at_from_kv(ctx.bindings.products(&Cluster::Devnet))
+            .await
+            .unwrap_or_else(|| "never".to_string());
+
+        let content = [
+            format!("**🐱 Catboard** `v{}`", env!("CARGO_PKG_VERSION")),
+            format!("Cluster: `{}`", Cluster::Devnet),
+            format!("Symbols: `{}` supported", symbols.len()),
+            format!("Last sync: `{}`", last_synced_at),
+            "<https://github.com/catboard-finance/catboard-bot>".to_string(),
+        ]
+        .join("\n");
+
+        InteractionResponse::message(content)
+    }
+}
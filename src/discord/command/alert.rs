@@ -0,0 +1,178 @@
+use async_trait::async_trait;
+
+use crate::{
+    catboard::{
+        alert::{add_alert, list_alerts_by_user, remove_alert, AlertDirection},
+        consumer::get_price_account_from_kv_by_symbol,
+    },
+    discord::{
+        command::{
+            definition::ApplicationCommandOption, CommandContext, CommandOptions, SlashCommand,
+            Visibility,
+        },
+        interaction::InteractionResponse,
+    },
+    solana::web3::Cluster,
+};
+
+pub(crate) struct Alert;
+
+#[async_trait(?Send)]
+impl SlashCommand for Alert {
+    fn name(&self) -> &'static str {
+        "alert"
+    }
+
+    fn description(&self) -> &'static str {
+        "Get pinged when a price goes above or below a target."
+    }
+
+    fn options(&self) -> Vec<ApplicationCommandOption> {
+        vec![
+            ApplicationCommandOption::sub_command("add", "Create a price alert in this channel.")
+                .option(
+                    ApplicationCommandOption::string("symbol", "Symbol to watch, e.g. SOL.")
+                        .required(),
+                )
+                .option(
+                    ApplicationCommandOption::string(
+                        "direction",
+                        "Fire when price goes above or below.",
+                    )
+                    .required()
+                    .choice("above", "above")
+                    .choice("below", "below"),
+                )
+                .option(
+                    ApplicationCommandOption::number("price", "Target price in USD.").required(),
+                )
+                .option(ApplicationCommandOption::boolean(
+                    "rearm",
+                    "Fire again each time the price crosses back.",
+                )),
+            ApplicationCommandOption::sub_command("list", "List your price alerts."),
+            ApplicationCommandOption::sub_command("remove", "Remove one of your price alerts.")
+                .option(
+                    ApplicationCommandOption::string(
+                        "id",
+                        "Alert id from /alert list, e.g. SOL-75LRNNBB4IZS.",
+                    )
+                    .required(),
+                ),
+        ]
+    }
+
+    fn examples(&self) -> Vec<&'static str> {
+        vec![
+            "/alert add symbol:SOL direction:above price:150",
+            "/alert remove id:SOL-75LRNNBB4IZS",
+        ]
+    }
+
+    fn visibility(&self) -> Visibility {
+        Visibility::Ephemeral
+    }
+
+    async fn handle(
+        &self,
+        ctx: &CommandContext<'_>,
+        options: &CommandOptions<'_>,
+    ) -> InteractionResponse {
+        let user_id = match ctx.interaction.invoker() {
+            Some(user) => user.id.as_str(),
+            None => return InteractionResponse::ephemeral("😱 Sorry! Who are you?".to_string()),
+        };
+
+        match options.sub_command() {
+            Some(("add", options)) => {
+                let direction = options
+                    .string("direction")
+                    .and_then(|direction| direction.parse::<AlertDirection>().ok());
+                match (options.string("symbol"), direction, options.number("price")) {
+                    (Some(symbol), Some(direction), Some(price)) => {
+                        let rearm = options.boolean("rearm").unwrap_or(false);
+                        create_alert(ctx, symbol, direction, price, rearm).await
+                    }
+                    _ => InteractionResponse::ephemeral(
+                        "Usage: `/alert add symbol direction price [rearm]`".to_string(),
+                    ),
+                }
+            }
+            Some(("list", _)) => {
+                let alerts = match list_alerts_by_user(ctx.bindings, user_id).await {
+                    Ok(alerts) => alerts,
+                    Err(error) => {
+                        return InteractionResponse::ephemeral(format!("😱 Sorry! {}", error))
+                    }
+                };
+                if alerts.is_empty() {
+                    return InteractionResponse::message(
+                        "🔕 No alerts yet, try `/alert add`.".to_string(),
+                    );
+                }
+
+                let lines: Vec<String> = alerts.iter().map(|alert| alert.to_string()).collect();
+                InteractionResponse::message(format!("**🔔 Your alerts**\n{}", lines.join("\n")))
+            }
+            Some(("remove", options)) => match options.string("id") {
+                Some(id) => match remove_alert(ctx.bindings, user_id, id).await {
+                    Ok(_) => InteractionResponse::message(format!(
+                        "🔕 Removed alert `{}`.",
+                        id.to_uppercase()
+                    )),
+                    Err(error) => InteractionResponse::ephemeral(format!("😱 Sorry! {}", error)),
+                },
+                None => InteractionResponse::ephemeral("Usage: `/alert remove id`".to_string()),
+            },
+            _ => InteractionResponse::ephemeral("Usage: `/alert add|list|remove`".to_string()),
+        }
+    }
+}
+
+/// Store an alert of the invoker, posted to the channel it was created in.
+pub(crate) async fn create_alert(
+    ctx: &CommandContext<'_>,
+    symbol: &str,
+    direction: AlertDirection,
+    price: f64,
+    rearm: bool,
+) -> InteractionResponse {
+    let (user_id, channel_id) = match (ctx.interaction.invoker(), &ctx.interaction.channel_id) {
+        (Some(user), Some(channel_id)) => (user.id.as_str(), channel_id.as_str()),
+        _ => {
+            return InteractionResponse::ephemeral(
+                "😱 Sorry! Alerts can only be created in a channel.".to_string(),
+            )
+        }
+    };
+
+    // Guard not support symbol
+    if get_price_account_from_kv_by_symbol(ctx.bindings.products(&Cluster::Devnet), symbol)
+        .await
+        .is_empty()
+    {
+        return InteractionResponse::ephemeral(format!(
+            "😱 Sorry! `{}` is not support at the moment.",
+            symbol
+        ));
+    }
+
+    match add_alert(
+        ctx.bindings,
+        &ctx.interaction.id,
+        user_id,
+        channel_id,
+        symbol,
+        direction,
+        price,
+        rearm,
+    )
+    .await
+    {
+        Ok(alert) => InteractionResponse::ephemeral(format!(
+            "🔔 Got it! Alert {} when `{}` goes {} `${:.2}`.",
+            alert.id, alert.symbol, alert.direction, alert.price
+        )),
+        Err(error) => InteractionResponse::ephemeral(format!("😱 Sorry! {}", error)),
+    }
+}
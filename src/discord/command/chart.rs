@@ -0,0 +1,116 @@
+use std::rc::Rc;
+
+use async_trait::async_trait;
+
+use crate::{
+    catboard::chart::{parse_range, render_symbol_chart, ChartStyle},
+    discord::{
+        command::{
+            definition::ApplicationCommandOption, CommandContext, CommandOptions, SlashCommand,
+        },
+        interaction::{Attachment, FollowUpMessage, InteractionResponse},
+        rest::DiscordClient,
+    },
+    error::Error,
+    kv::bindings::Bindings,
+    solana::web3::Cluster,
+    task::Task,
+};
+
+pub(crate) struct Chart;
+
+/// Render the chart of `symbol` and post it as `followup` with `discord`, files can't go in the
+/// JSON response.
+async fn send_chart(
+    bindings: Rc<Bindings>,
+    discord: DiscordClient,
+    mut followup: FollowUpMessage,
+    symbol: String,
+    days: i64,
+    style: ChartStyle,
+) -> Result<(), Error> {
+    match render_symbol_chart(bindings.prices(&Cluster::Devnet), &symbol, days, style).await {
+        Some(bytes) => followup.attachments.push(Attachment {
+            filename: format!("{}.png", symbol),
+            content_type: "image/png".to_string(),
+            bytes,
+        }),
+        None => followup.content = format!("😱 Sorry! No price history of `{}` yet.", symbol),
+    }
+
+    discord.create_followup_message(&followup).await
+}
+
+#[async_trait(?Send)]
+impl SlashCommand for Chart {
+    fn name(&self) -> &'static str {
+        "chart"
+    }
+
+    fn description(&self) -> &'static str {
+        "Chart the daily price history of a symbol."
+    }
+
+    fn options(&self) -> Vec<ApplicationCommandOption> {
+        vec![
+            ApplicationCommandOption::string("symbol", "Symbol to chart, e.g. SOL.").required(),
+            ApplicationCommandOption::string("range", "How far back, 30d by default.")
+                .choice("7 days", "7d")
+                .choice("30 days", "30d")
+                .choice("90 days", "90d"),
+            ApplicationCommandOption::string("style", "Line or candlestick, line by default.")
+                .choice("line", "line")
+                .choice("candlestick", "candle"),
+        ]
+    }
+
+    fn examples(&self) -> Vec<&'static str> {
+        vec![
+            "/chart symbol:SOL",
+            "/chart symbol:BTC range:90d style:candle",
+        ]
+    }
+
+    async fn handle(
+        &self,
+        ctx: &CommandContext<'_>,
+        options: &CommandOptions<'_>,
+    ) -> InteractionResponse {
+        let symbol = match options.string("symbol") {
+            Some(symbol) => symbol.to_uppercase(),
+            None => {
+                return InteractionResponse::ephemeral(
+                    "Usage: `/chart symbol [range] [style]`".to_string(),
+                )
+            }
+        };
+        let range = options.string("range").unwrap_or("30d");
+        let days = match parse_range(range) {
+            Some(days) => days,
+            None => {
+                return InteractionResponse::ephemeral(format!(
+                    "😱 Sorry! `{}` is not a valid range, try `30d`.",
+                    range
+                ))
+            }
+        };
+        let style = options
+            .string("style")
+            .and_then(ChartStyle::from_option)
+            .unwrap_or(ChartStyle::Line);
+
+        // Rendering reads up to 90 days of history, answer before it starts
+        let followup = FollowUpMessage {
+            application_id: ctx.interaction.application_id.clone(),
+            interaction_token: ctx.interaction.token.clone(),
+            content: format!("📈 `{}` last `{}`", symbol, range),
+            attachments: Vec::new(),
+            flags: None,
+        };
+        // Follow-ups are authorized by the interaction token
+        let discord = ctx.endpoints.discord("");
+        InteractionResponse::deferred().with_task(Task::new(move |bindings| {
+            send_chart(bindings, discord, followup, symbol, days, style)
+        }))
+    }
+}
@@ -0,0 +1,95 @@
+use async_trait::async_trait;
+
+use crate::{
+    catboard::convert::{convert, fetch_usd_rates, format_amount},
+    discord::{
+        command::{
+            definition::ApplicationCommandOption, CommandContext, CommandOptions, SlashCommand,
+        },
+        interaction::InteractionResponse,
+    },
+    solana::web3::Cluster,
+};
+
+pub(crate) struct Convert;
+
+#[async_trait(?Send)]
+impl SlashCommand for Convert {
+    fn name(&self) -> &'static str {
+        "convert"
+    }
+
+    fn description(&self) -> &'static str {
+        "Convert an amount between crypto and fiat using Pyth prices."
+    }
+
+    fn options(&self) -> Vec<ApplicationCommandOption> {
+        vec![
+            ApplicationCommandOption::number("amount", "Amount to convert, e.g. 3.5.").required(),
+            ApplicationCommandOption::string("from", "Currency of the amount, e.g. SOL.")
+                .required(),
+            ApplicationCommandOption::string("to", "Currency to convert to, e.g. USD.").required(),
+        ]
+    }
+
+    fn examples(&self) -> Vec<&'static str> {
+        vec![
+            "/convert amount:3.5 from:SOL to:USD",
+            "/convert amount:100 from:USD to:ETH",
+        ]
+    }
+
+    async fn handle(
+        &self,
+        ctx: &CommandContext<'_>,
+        options: &CommandOptions<'_>,
+    ) -> InteractionResponse {
+        let (amount, from, to) = match (
+            options.number("amount"),
+            options.string("from"),
+            options.string("to"),
+        ) {
+            (Some(amount), Some(from), Some(to)) => {
+                (amount, from.trim().to_uppercase(), to.trim().to_uppercase())
+            }
+            _ => {
+                return InteractionResponse::ephemeral(
+                    "Usage: `/convert amount from to`".to_string(),
+                )
+            }
+        };
+        if !amount.is_finite() || amount <= 0.0 {
+            return InteractionResponse::ephemeral(
+                "😱 Sorry! amount must be positive.".to_string(),
+            );
+        }
+
+        let rates =
+            match fetch_usd_rates(ctx.bindings, ctx.endpoints, &Cluster::Devnet, &[&from, &to])
+                .await
+            {
+                Ok(rates) => rates,
+                Err(error) => {
+                    return InteractionResponse::ephemeral(format!("😱 Sorry! {}", error))
+                }
+            };
+        let conversion = convert(amount, rates[0], rates[1]);
+
+        // Unbounded above when `to` is worth no more than its confidence
+        let range = match conversion.high {
+            Some(high) => format!(
+                "`{}` ~ `{}`",
+                format_amount(conversion.low, &to),
+                format_amount(high, &to)
+            ),
+            None => format!("≥ `{}`", format_amount(conversion.low, &to)),
+        };
+
+        InteractionResponse::message(format!(
+            "`{}` = `{}` 🎯{}",
+            format_amount(amount, &from),
+            format_amount(conversion.value, &to),
+            range,
+        ))
+    }
+}
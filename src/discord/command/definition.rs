@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+
+use crate::discord::interaction::ApplicationCommandOptionType;
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub(crate) struct ApplicationCommandOptionChoice {
+    pub(crate) name: String,
+    pub(crate) value: serde_json::Value,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub(crate) struct ApplicationCommandOption {
+    #[serde(rename = "type")]
+    pub(crate) ty: ApplicationCommandOptionType,
+    pub(crate) name: String,
+    pub(crate) description: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) required: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) choices: Vec<ApplicationCommandOptionChoice>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) options: Vec<ApplicationCommandOption>,
+}
+
+#[allow(dead_code)]
+impl ApplicationCommandOption {
+    pub(crate) fn new(ty: ApplicationCommandOptionType, name: &str, description: &str) -> Self {
+        ApplicationCommandOption {
+            ty,
+            name: name.to_string(),
+            description: description.to_string(),
+            required: false,
+            choices: Vec::new(),
+            options: Vec::new(),
+        }
+    }
+
+    pub(crate) fn string(name: &str, description: &str) -> Self {
+        Self::new(ApplicationCommandOptionType::String, name, description)
+    }
+
+    pub(crate) fn number(name: &str, description: &str) -> Self {
+        Self::new(ApplicationCommandOptionType::Number, name, description)
+    }
+
+    pub(crate) fn boolean(name: &str, description: &str) -> Self {
+        Self::new(ApplicationCommandOptionType::Boolean, name, description)
+    }
+
+    pub(crate) fn sub_command(name: &str, description: &str) -> Self {
+        Self::new(ApplicationCommandOptionType::SubCommand, name, description)
+    }
+
+    pub(crate) fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    pub(crate) fn choice(mut self, name: &str, value: &str) -> Self {
+        self.choices.push(ApplicationCommandOptionChoice {
+            name: name.to_string(),
+            value: serde_json::Value::String(value.to_string()),
+        });
+        self
+    }
+
+    pub(crate) fn option(mut self, option: ApplicationCommandOption) -> Self {
+        self.options.push(option);
+        self
+    }
+}
+
+/// Command definition as Discord expects it when registering application commands.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub(crate) struct ApplicationCommand {
+    pub(crate) name: String,
+    pub(crate) description: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) options: Vec<ApplicationCommandOption>,
+}
@@ -1,23 +1,40 @@
-use crate::discord::interaction::{
-    InteractionApplicationCommandCallbackData, InteractionResponse, InteractionResponseType,
+use async_trait::async_trait;
+
+use crate::discord::{
+    command::{CommandContext, CommandOptions, SlashCommand},
+    interaction::InteractionResponse,
 };
 
-pub(crate) async fn hello() -> InteractionResponse {
-    let content = "Hello there!".to_string();
-    // let embed = json!({
-    //   "type": "rich",
-    //   "title": "Solana",
-    //   "description": "foo",
-    //   "color": 0x8400ff,
-    // })
-    // .to_string();
-    // let embeds: Vec<String> = [embed].to_vec();
+pub(crate) struct Hello;
+
+#[async_trait(?Send)]
+impl SlashCommand for Hello {
+    fn name(&self) -> &'static str {
+        "hello"
+    }
+
+    fn description(&self) -> &'static str {
+        "Say hello."
+    }
+
+    async fn handle(
+        &self,
+        ctx: &CommandContext<'_>,
+        _options: &CommandOptions<'_>,
+    ) -> InteractionResponse {
+        let content = match ctx.interaction.invoker() {
+            Some(user) => format!("Hello there, {}!", user.username),
+            None => "Hello there!".to_string(),
+        };
+        // let embed = json!({
+        //   "type": "rich",
+        //   "title": "Solana",
+        //   "description": "foo",
+        //   "color": 0x8400ff,
+        // })
+        // .to_string();
+        // let embeds: Vec<String> = [embed].to_vec();
 
-    InteractionResponse {
-        ty: InteractionResponseType::ChannelMessageWithSource,
-        data: Some(InteractionApplicationCommandCallbackData {
-            content: content,
-            embeds: None,
-        }),
+        InteractionResponse::message(content)
     }
 }
@@ -0,0 +1,163 @@
+use async_trait::async_trait;
+
+use crate::discord::{
+    command::{
+        definition::{ApplicationCommand, ApplicationCommandOption},
+        CommandContext, CommandOptions, CommandRegistry, SlashCommand, Visibility,
+        VISIBILITY_OPTION,
+    },
+    interaction::{ApplicationCommandOptionType, InteractionResponse},
+};
+
+pub(crate) struct Help;
+
+#[async_trait(?Send)]
+impl SlashCommand for Help {
+    fn name(&self) -> &'static str {
+        "help"
+    }
+
+    fn description(&self) -> &'static str {
+        "List commands and how to use them."
+    }
+
+    fn options(&self) -> Vec<ApplicationCommandOption> {
+        vec![ApplicationCommandOption::string(
+            "command",
+            "Show details of one command, e.g. price.",
+        )]
+    }
+
+    fn examples(&self) -> Vec<&'static str> {
+        vec!["/help", "/help command:price"]
+    }
+
+    fn visibility(&self) -> Visibility {
+        Visibility::Ephemeral
+    }
+
+    async fn handle(
+        &self,
+        _ctx: &CommandContext<'_>,
+        options: &CommandOptions<'_>,
+    ) -> InteractionResponse {
+        let registry = CommandRegistry::default();
+
+        match options.string("command") {
+            Some(name) => match registry.get(name.trim_start_matches('/')) {
+                Some(command) => InteractionResponse::message(command_help(command)),
+                None => InteractionResponse::ephemeral(format!(
+                    "😱 Sorry! `/{}` is not support. Try `/help` to see what I can do.",
+                    name.trim_start_matches('/')
+                )),
+            },
+            None => InteractionResponse::message(help(&registry)),
+        }
+    }
+}
+
+fn is_sub_command(option: &ApplicationCommandOption) -> bool {
+    option.ty == ApplicationCommandOptionType::SubCommand
+}
+
+fn params(options: &[ApplicationCommandOption]) -> String {
+    options
+        .iter()
+        .filter(|option| option.name != VISIBILITY_OPTION)
+        .map(|option| match option.required {
+            true => format!(" {}", option.name),
+            false => format!(" [{}]", option.name),
+        })
+        .collect()
+}
+
+/// One `(usage, description)` line per command or sub command.
+fn usages(definition: &ApplicationCommand) -> Vec<(String, String)> {
+    if definition.options.iter().any(is_sub_command) {
+        definition
+            .options
+            .iter()
+            .filter(|option| is_sub_command(option))
+            .map(|sub| {
+                (
+                    format!("/{} {}{}", definition.name, sub.name, params(&sub.options)),
+                    sub.description.clone(),
+                )
+            })
+            .collect()
+    } else {
+        vec![(
+            format!("/{}{}", definition.name, params(&definition.options)),
+            definition.description.clone(),
+        )]
+    }
+}
+
+fn examples(command: &dyn SlashCommand) -> String {
+    let examples: Vec<String> = command
+        .examples()
+        .iter()
+        .map(|example| format!("`{}`", example))
+        .collect();
+
+    examples.join(", ")
+}
+
+pub(crate) fn help(registry: &CommandRegistry) -> String {
+    let mut lines = vec!["**🐱 Catboard commands**".to_string()];
+
+    for command in registry.commands() {
+        for (usage, description) in usages(&command.definition()) {
+            lines.push(format!("`{}` {}", usage, description));
+        }
+        if !command.examples().is_empty() {
+            lines.push(format!("  ↳ e.g. {}", examples(command)));
+        }
+    }
+
+    lines.push(format!(
+        "_Add `{}: only me` to a command to keep the reply to yourself._",
+        VISIBILITY_OPTION
+    ));
+    lines.join("\n")
+}
+
+fn command_help(command: &dyn SlashCommand) -> String {
+    let definition = command.definition();
+    let mut lines = Vec::new();
+
+    for (usage, description) in usages(&definition) {
+        lines.push(format!("`{}` {}", usage, description));
+    }
+
+    let options = definition
+        .options
+        .iter()
+        .flat_map(|option| match is_sub_command(option) {
+            true => option.options.iter().collect::<Vec<_>>(),
+            false => vec![option],
+        })
+        .filter(|option| option.name != VISIBILITY_OPTION);
+    for option in options {
+        lines.push(format!("  • `{}` {}", option.name, option.description));
+    }
+
+    if !command.examples().is_empty() {
+        lines.push(format!("  ↳ e.g. {}", examples(command)));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+#[test]
+fn test_help_lists_every_command() {
+    let registry = CommandRegistry::default();
+    let text = help(&registry);
+
+    for command in registry.commands() {
+        assert!(text.contains(&format!("`/{}", command.name())));
+    }
+    assert!(text.contains("`/price symbol` Get the latest price of a symbol from Pyth."));
+    assert!(text.contains("`/help [command]`"));
+    assert!(!text.contains("[visibility]"));
+}
@@ -0,0 +1,66 @@
+use async_trait::async_trait;
+
+use crate::{
+    catboard::{
+        consumer::get_pair_by_symbol,
+        indicators::{get_indicators, IndicatorConfig},
+    },
+    discord::{
+        command::{
+            definition::ApplicationCommandOption, CommandContext, CommandOptions, SlashCommand,
+        },
+        interaction::InteractionResponse,
+    },
+    solana::web3::Cluster,
+};
+
+pub(crate) struct Indicators;
+
+#[async_trait(?Send)]
+impl SlashCommand for Indicators {
+    fn name(&self) -> &'static str {
+        "indicators"
+    }
+
+    fn description(&self) -> &'static str {
+        "Show change, moving averages, RSI and Bollinger bands of a symbol."
+    }
+
+    fn options(&self) -> Vec<ApplicationCommandOption> {
+        vec![ApplicationCommandOption::string("symbol", "Symbol to analyze, e.g. SOL.").required()]
+    }
+
+    fn examples(&self) -> Vec<&'static str> {
+        vec!["/indicators symbol:SOL"]
+    }
+
+    async fn handle(
+        &self,
+        ctx: &CommandContext<'_>,
+        options: &CommandOptions<'_>,
+    ) -> InteractionResponse {
+        let symbol = match options.string("symbol") {
+            Some(symbol) => symbol.to_uppercase(),
+            None => {
+                return InteractionResponse::ephemeral("Usage: `/indicators symbol`".to_string())
+            }
+        };
+
+        match get_indicators(
+            ctx.bindings.prices(&Cluster::Devnet),
+            &get_pair_by_symbol(&symbol),
+            &IndicatorConfig::default(),
+        )
+        .await
+        {
+            Some(indicators) => InteractionResponse::message(format!(
+                "**📊 `{}` daily indicators**\n{}",
+                symbol, indicators
+            )),
+            None => InteractionResponse::ephemeral(format!(
+                "😱 Sorry! No price history of `{}` yet.",
+                symbol
+            )),
+        }
+    }
+}
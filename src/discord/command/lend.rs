@@ -0,0 +1,145 @@
+use async_trait::async_trait;
+
+use crate::{
+    discord::{
+        command::{
+            definition::ApplicationCommandOption, CommandContext, CommandOptions, SlashCommand,
+        },
+        interaction::{Component, InteractionResponse},
+    },
+    lending::{fetch_lending_pool, lending_protocols},
+    solana::web3::Cluster,
+};
+
+pub(crate) struct Lend;
+
+#[async_trait(?Send)]
+impl SlashCommand for Lend {
+    fn name(&self) -> &'static str {
+        "lend"
+    }
+
+    fn description(&self) -> &'static str {
+        "Show lending and borrowing rates of an asset."
+    }
+
+    fn options(&self) -> Vec<ApplicationCommandOption> {
+        vec![ApplicationCommandOption::string("asset", "Asset to lend, e.g. USDC.").required()]
+    }
+
+    fn examples(&self) -> Vec<&'static str> {
+        vec!["/lend asset:USDC"]
+    }
+
+    async fn handle(
+        &self,
+        ctx: &CommandContext<'_>,
+        options: &CommandOptions<'_>,
+    ) -> InteractionResponse {
+        let asset = match options.string("asset") {
+            Some(asset) => asset.to_uppercase(),
+            None => return InteractionResponse::ephemeral("Usage: `/lend asset`".to_string()),
+        };
+
+        let mut lines = vec![format!("**🏦 `{}` lending rates**", asset)];
+        let mut buttons = Vec::new();
+        let mut supported = false;
+        for protocol in lending_protocols() {
+            let pool = match fetch_lending_pool(
+                ctx.bindings,
+                ctx.endpoints,
+                &Cluster::MainnetBeta,
+                protocol.as_ref(),
+                &asset,
+            )
+            .await
+            {
+                Ok(Some(pool)) => pool,
+                Ok(None) => continue,
+                Err(error) => {
+                    supported = true;
+                    lines.push(format!("**{}** unavailable · {}", protocol.name(), error));
+                    continue;
+                }
+            };
+            supported = true;
+
+            lines.push(format!(
+                "**{}** supply `{:.2}%` · borrow `{:.2}%` APY",
+                protocol.name(),
+                pool.supply_apy(),
+                pool.borrow_apy()
+            ));
+            lines.push(format!(
+                "  ↳ utilization `{:.1}%` · available `{:.2} {}`",
+                pool.utilization(),
+                pool.available,
+                asset
+            ));
+            buttons.push(Component::link_button(
+                protocol.url(),
+                &format!("Lend on {}", protocol.name()),
+            ));
+        }
+
+        if !supported {
+            return InteractionResponse::ephemeral(format!(
+                "😱 Sorry! `{}` lending is not support at the moment.",
+                asset
+            ));
+        }
+
+        let response = InteractionResponse::message(lines.join("\n"));
+        if buttons.is_empty() {
+            return response;
+        }
+
+        response.with_components(vec![Component::action_row(buttons)])
+    }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_lend_shows_unavailable_protocols() {
+    use serde_json::json;
+
+    use crate::{
+        lending::{francium::Francium, LendingProtocol},
+        testing::e2e::Harness,
+    };
+
+    let harness = Harness::start().await;
+    // Configured, but not an account the RPC knows
+    harness
+        .bindings
+        .products(&Cluster::MainnetBeta)
+        .put_text(
+            &Francium.pool_account_key("USDT"),
+            "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB",
+            60,
+        )
+        .await
+        .unwrap();
+
+    let response = harness
+        .command(
+            "lend",
+            json!([{ "type": 3, "name": "asset", "value": "usdt" }]),
+        )
+        .await;
+
+    assert_eq!(
+        response["data"]["content"],
+        "**🏦 `USDT` lending rates**\n\
+         **Francium** unavailable · Not found: Francium pool of USDT."
+    );
+    assert_eq!(response["data"]["components"], json!(null));
+
+    let response = harness
+        .command(
+            "lend",
+            json!([{ "type": 3, "name": "asset", "value": "bonk" }]),
+        )
+        .await;
+    assert_eq!(response["data"]["flags"], 64);
+}
@@ -1,41 +1,173 @@
+use async_trait::async_trait;
+
 use crate::{
-    catboard::consumer::{get_formatted_price_from_pyth, get_price_account_from_kv_by_symbol},
-    cloudflare::worker::WorkersKv,
-    discord::interaction::{
-        InteractionApplicationCommandCallbackData, InteractionResponse, InteractionResponseType,
+    catboard::{
+        alert::AlertDirection,
+        consumer::{format_price, get_pair_by_symbol, get_price_account_from_kv_by_symbol},
+        indicators::get_indicator_summary,
+        price_cache::{lookup_price, revalidate_price, PriceSource},
+    },
+    discord::{
+        command::{
+            alert::create_alert, custom_id, definition::ApplicationCommandOption, CommandContext,
+            CommandOptions, SlashCommand,
+        },
+        interaction::{
+            ButtonStyle, Component, InteractionResponse, MessageComponentInteractionData,
+            ModalSubmitInteractionData, TextInputStyle,
+        },
     },
-    solana::web3::Cluster,
+    endpoints::Endpoints,
+    error::Error,
+    kv::bindings::Bindings,
+    solana::{pubkey::Pubkey, web3::Cluster},
 };
+use std::str::FromStr;
+
+pub(crate) struct Price;
+
+#[async_trait(?Send)]
+impl SlashCommand for Price {
+    fn name(&self) -> &'static str {
+        "price"
+    }
+
+    fn description(&self) -> &'static str {
+        "Get the latest price of a symbol from Pyth."
+    }
+
+    fn options(&self) -> Vec<ApplicationCommandOption> {
+        vec![ApplicationCommandOption::string("symbol", "Symbol to look up, e.g. SOL.").required()]
+    }
+
+    fn examples(&self) -> Vec<&'static str> {
+        vec!["/price symbol:SOL"]
+    }
+
+    async fn handle(
+        &self,
+        ctx: &CommandContext<'_>,
+        options: &CommandOptions<'_>,
+    ) -> InteractionResponse {
+        match options.string("symbol") {
+            Some(symbol) => price(ctx.bindings, ctx.endpoints, symbol).await,
+            None => InteractionResponse::ephemeral("Usage: `/price symbol`".to_string()),
+        }
+    }
+
+    async fn handle_component(
+        &self,
+        _ctx: &CommandContext<'_>,
+        action: &str,
+        _data: &MessageComponentInteractionData,
+    ) -> InteractionResponse {
+        match action.split_once(':') {
+            Some(("alert", symbol)) => alert_modal(symbol),
+            _ => InteractionResponse::ephemeral(format!("😱 Sorry! `{}` is not support.", action)),
+        }
+    }
 
-pub(crate) async fn price(kv: &WorkersKv, symbol: &str) -> InteractionResponse {
+    async fn handle_modal_submit(
+        &self,
+        ctx: &CommandContext<'_>,
+        action: &str,
+        data: &ModalSubmitInteractionData,
+    ) -> InteractionResponse {
+        match action.split_once(':') {
+            Some(("alert", symbol)) => match parse_alert(data) {
+                Ok((direction, target)) => {
+                    create_alert(ctx, symbol, direction, target, false).await
+                }
+                Err(error) => InteractionResponse::ephemeral(format!("😱 Sorry! {}", error)),
+            },
+            _ => InteractionResponse::ephemeral(format!("😱 Sorry! `{}` is not support.", action)),
+        }
+    }
+}
+
+fn alert_modal(symbol: &str) -> InteractionResponse {
+    InteractionResponse::modal(
+        &custom_id("price", &format!("alert:{}", symbol)),
+        &format!("Create {} price alert", symbol),
+        vec![
+            Component::text_input("direction", "Direction", TextInputStyle::Short)
+                .placeholder("above or below")
+                .max_length(5),
+            Component::text_input("price", "Target price (USD)", TextInputStyle::Short)
+                .placeholder("100.00")
+                .max_length(20),
+        ],
+    )
+}
+
+fn parse_alert(data: &ModalSubmitInteractionData) -> Result<(AlertDirection, f64), Error> {
+    let direction = data.parse::<AlertDirection>("direction")?;
+
+    let target = data.parse::<f64>("price")?;
+    if !target.is_finite() || target <= 0.0 {
+        return Err(Error::InvalidPayload("price must be positive".to_string()));
+    }
+
+    Ok((direction, target))
+}
+
+pub(crate) async fn price(
+    bindings: &Bindings,
+    endpoints: &Endpoints,
+    symbol: &str,
+) -> InteractionResponse {
     // Get price_account from kv
-    let price_account = get_price_account_from_kv_by_symbol(&kv, &symbol).await;
-    let embeds = None;
+    let price_account =
+        get_price_account_from_kv_by_symbol(bindings.products(&Cluster::Devnet), symbol).await;
 
     // Guard not support symbol
-    match price_account.as_str() {
-        "" => {
-            let content = format!("😱 Sorry! `{}` is not support at the moment.", symbol);
-            return InteractionResponse {
-                ty: InteractionResponseType::ChannelMessageWithSource,
-                data: Some(InteractionApplicationCommandCallbackData {
-                    content: content,
-                    embeds: embeds,
-                }),
-            };
-        }
-        _ => {}
+    if price_account.is_empty() {
+        let content = format!("😱 Sorry! `{}` is not support at the moment.", symbol);
+        return InteractionResponse::ephemeral(content);
     }
 
-    // Get formatted price
-    let content =
-        get_formatted_price_from_pyth(&Cluster::Devnet, symbol, &price_account.as_str()).await;
+    // Cached price while fresh, live from Pyth otherwise
+    let cluster = Cluster::Devnet;
+    let rpc = endpoints.rpc(&cluster);
+    let pair = get_pair_by_symbol(symbol);
+    let px_pkey = match Pubkey::from_str(&price_account) {
+        Ok(px_pkey) => px_pkey,
+        Err(_) => {
+            let content = format!("😱 Sorry! `{}` price account is invalid.", symbol);
+            return InteractionResponse::ephemeral(content);
+        }
+    };
+    let lookup = match lookup_price(bindings.prices(&cluster), &rpc, &pair, &px_pkey).await {
+        Ok(Some(lookup)) => lookup,
+        Ok(None) => {
+            let content = format!("😱 Sorry! `{}` price is not available.", symbol);
+            return InteractionResponse::ephemeral(content);
+        }
+        Err(error) => {
+            let content = format!("😱 Sorry! `{}` price is not available · {}", symbol, error);
+            return InteractionResponse::ephemeral(content);
+        }
+    };
+    let mut content = format!(
+        "{} · {}",
+        format_price(symbol, &lookup.price_conf),
+        lookup.indicator()
+    );
+
+    // Indicators kept by the price sync, if any
+    if let Some(summary) = get_indicator_summary(bindings.prices(&cluster), &pair).await {
+        content = format!("{}\n{}", content, summary);
+    }
+    let symbol = symbol.to_uppercase();
+    let components = vec![Component::action_row(vec![Component::button(
+        &custom_id("price", &format!("alert:{}", symbol)),
+        "🔔 Create alert",
+        ButtonStyle::Secondary,
+    )])];
 
-    InteractionResponse {
-        ty: InteractionResponseType::ChannelMessageWithSource,
-        data: Some(InteractionApplicationCommandCallbackData {
-            content: content,
-            embeds: embeds,
-        }),
+    let response = InteractionResponse::message(content).with_components(components);
+    match lookup.source {
+        PriceSource::Stale => response.with_task(revalidate_price(cluster, rpc, pair, px_pkey)),
+        _ => response,
     }
 }
@@ -0,0 +1,93 @@
+use async_trait::async_trait;
+use chrono::Utc;
+
+use crate::{
+    catboard::{convert::fetch_usd_rates, history::read_daily_history},
+    discord::{
+        command::{CommandContext, CommandOptions, SlashCommand},
+        interaction::{Component, InteractionResponse},
+    },
+    kv::KeyValueStore,
+    marinade::state::{
+        estimate_apy, fetch_marinade_state, MarinadeState, MARINADE_STAKE_URL, MSOL_PAIR,
+    },
+    solana::web3::{get_epoch_info, Cluster},
+};
+
+const APY_DAYS: i64 = 30;
+
+pub(crate) struct Stake;
+
+#[async_trait(?Send)]
+impl SlashCommand for Stake {
+    fn name(&self) -> &'static str {
+        "stake"
+    }
+
+    fn description(&self) -> &'static str {
+        "Show Marinade liquid staking rate, TVL and APY."
+    }
+
+    async fn handle(
+        &self,
+        ctx: &CommandContext<'_>,
+        _options: &CommandOptions<'_>,
+    ) -> InteractionResponse {
+        let state = match fetch_marinade_state(&ctx.endpoints.rpc(&Cluster::MainnetBeta)).await {
+            Ok(state) => state,
+            Err(error) => return InteractionResponse::ephemeral(format!("😱 Sorry! {}", error)),
+        };
+
+        let apy =
+            estimate_apy_from_history(ctx.bindings.prices(&Cluster::MainnetBeta), &state).await;
+        let sol_usd = fetch_usd_rates(ctx.bindings, ctx.endpoints, &Cluster::Devnet, &["SOL"])
+            .await
+            .ok()
+            .map(|rates| rates[0].price);
+        let epoch_info = get_epoch_info(&ctx.endpoints.rpc(&Cluster::MainnetBeta)).await;
+
+        let mut lines = vec![
+            "**🥩 Marinade staking**".to_string(),
+            format!("1 mSOL = `{:.6} SOL`", state.msol_price()),
+        ];
+        if let Some(sol_usd) = sol_usd {
+            lines.push(format!("mSOL price `${:.2}`", state.msol_price() * sol_usd));
+        }
+        lines.push(format!(
+            "APY `{}` ({}% reward fee)",
+            apy.map(|apy| format!("{:.2}%", apy))
+                .unwrap_or_else(|| "n/a".to_string()),
+            state.reward_fee_bps as f64 / 100.0
+        ));
+        lines.push(format!(
+            "TVL `{:.0} SOL` · supply `{:.0} mSOL` · `{}` validators",
+            state.tvl_sol(),
+            state.msol_supply(),
+            state.validator_count
+        ));
+        if let Some(epoch_info) = epoch_info {
+            lines.push(format!(
+                "Epoch `{}` `{:.0}%` done, last stake delta at epoch `{}`",
+                epoch_info.epoch,
+                epoch_info.progress() * 100.0,
+                state.last_stake_delta_epoch
+            ));
+        }
+
+        let components = vec![Component::action_row(vec![Component::link_button(
+            MARINADE_STAKE_URL,
+            "Stake on Marinade",
+        )])];
+
+        InteractionResponse::message(lines.join("\n")).with_components(components)
+    }
+}
+
+/// Estimate the APY from the oldest mSOL price in range, recorded by the price sync.
+async fn estimate_apy_from_history(kv: &dyn KeyValueStore, state: &MarinadeState) -> Option<f64> {
+    let today = Utc::now().naive_utc().date();
+    let history = read_daily_history(kv, MSOL_PAIR, today, APY_DAYS).await;
+    let (date, oldest) = history.first()?;
+
+    estimate_apy(oldest.close, state.msol_price(), (today - *date).num_days())
+}
@@ -0,0 +1,180 @@
+use async_trait::async_trait;
+
+use crate::{
+    discord::{
+        command::{
+            definition::ApplicationCommandOption, CommandContext, CommandOptions, SlashCommand,
+        },
+        interaction::{Component, InteractionResponse},
+    },
+    error::Error,
+    jupiter::{
+        quote::{swap_url, JupiterClient},
+        token::find_token_by_symbol,
+    },
+};
+
+const DEFAULT_SLIPPAGE_BPS: u16 = 50;
+const MAX_SLIPPAGE_PERCENT: f64 = 50.0;
+
+pub(crate) struct SwapQuote;
+
+#[async_trait(?Send)]
+impl SlashCommand for SwapQuote {
+    fn name(&self) -> &'static str {
+        "swap_quote"
+    }
+
+    fn description(&self) -> &'static str {
+        "Quote a token swap on Jupiter."
+    }
+
+    fn options(&self) -> Vec<ApplicationCommandOption> {
+        vec![
+            ApplicationCommandOption::string("from", "Token to sell, e.g. SOL.").required(),
+            ApplicationCommandOption::string("to", "Token to buy, e.g. USDC.").required(),
+            ApplicationCommandOption::number("amount", "Amount of from to sell, e.g. 1.5.")
+                .required(),
+            ApplicationCommandOption::number(
+                "slippage",
+                "Max slippage in percent, 0.5 by default.",
+            ),
+        ]
+    }
+
+    fn examples(&self) -> Vec<&'static str> {
+        vec!["/swap_quote from:SOL to:USDC amount:1.5"]
+    }
+
+    async fn handle(
+        &self,
+        ctx: &CommandContext<'_>,
+        options: &CommandOptions<'_>,
+    ) -> InteractionResponse {
+        let (from, to, amount) = match (
+            options.string("from"),
+            options.string("to"),
+            options.number("amount"),
+        ) {
+            (Some(from), Some(to), Some(amount)) => (from, to, amount),
+            _ => {
+                return InteractionResponse::ephemeral(
+                    "Usage: `/swap_quote from to amount [slippage]`".to_string(),
+                )
+            }
+        };
+        let slippage_bps = match options.number("slippage").map(slippage_to_bps) {
+            None => DEFAULT_SLIPPAGE_BPS,
+            Some(Some(bps)) => bps,
+            Some(None) => {
+                return InteractionResponse::ephemeral(format!(
+                    "Usage: `/swap_quote from to amount [slippage]`, slippage must be above 0% and at most {}%",
+                    MAX_SLIPPAGE_PERCENT
+                ))
+            }
+        };
+
+        match swap_quote(&ctx.endpoints.jupiter(), from, to, amount, slippage_bps).await {
+            Ok(response) => response,
+            Err(error) => InteractionResponse::ephemeral(format!("😱 Sorry! {}", error)),
+        }
+    }
+}
+
+/// Slippage percent as basis points, `None` outside (0, 50]%.
+fn slippage_to_bps(slippage: f64) -> Option<u16> {
+    if slippage > 0.0 && slippage <= MAX_SLIPPAGE_PERCENT {
+        Some(((slippage * 100.0).round() as u16).max(1))
+    } else {
+        None
+    }
+}
+
+pub(crate) async fn swap_quote(
+    client: &JupiterClient,
+    from: &str,
+    to: &str,
+    amount: f64,
+    slippage_bps: u16,
+) -> Result<InteractionResponse, Error> {
+    let input = find_token_by_symbol(from)
+        .ok_or_else(|| Error::NotFound(format!("token {}", from.to_uppercase())))?;
+    let output = find_token_by_symbol(to)
+        .ok_or_else(|| Error::NotFound(format!("token {}", to.to_uppercase())))?;
+    let raw_amount = input
+        .to_raw(amount)
+        .ok_or_else(|| Error::InvalidPayload(format!("amount is not valid: {}", amount)))?;
+
+    let quote = client
+        .quote(&input, &output, raw_amount, slippage_bps)
+        .await?;
+
+    let content = [
+        format!(
+            "**🪐 `{} {}` → `{} {}`**",
+            amount,
+            input.symbol,
+            output.to_ui(quote.out_amount()?),
+            output.symbol
+        ),
+        format!(
+            "Minimum received `{} {}` at `{:.2}%` slippage",
+            output.to_ui(quote.min_out_amount()?),
+            output.symbol,
+            quote.slippage_bps as f64 / 100.0
+        ),
+        format!("Price impact `{:.4}%`", quote.price_impact()),
+        format!("Route `{}` via {}", quote.hops(), quote.venues()),
+    ]
+    .join("\n");
+    let components = vec![Component::action_row(vec![Component::link_button(
+        &swap_url(&input, &output),
+        "Swap on Jupiter",
+    )])];
+
+    Ok(InteractionResponse::message(content).with_components(components))
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_swap_quote_message() {
+    use crate::testing::MockServer;
+
+    let server = MockServer::start(|_request| {
+        (
+            200,
+            include_str!("../../../tests/fixtures/jupiter/quote_sol_usdc.json").to_string(),
+        )
+    })
+    .await;
+    let client = JupiterClient::with_api_url(&server.url());
+
+    let response = swap_quote(&client, "sol", "usdc", 1.0, 50).await.unwrap();
+    let json = serde_json::to_value(&response).unwrap();
+
+    assert_eq!(
+        json["data"]["content"],
+        "**🪐 `1 SOL` → `145.231876 USDC`**\n\
+         Minimum received `144.505717 USDC` at `0.50%` slippage\n\
+         Price impact `0.1200%`\n\
+         Route `SOL → USDT → USDC` via Whirlpool, Meteora DLMM"
+    );
+    assert_eq!(
+        json["data"]["components"][0]["components"][0]["url"],
+        "https://jup.ag/swap/SOL-USDC"
+    );
+    assert!(swap_quote(&client, "nope", "usdc", 1.0, 50).await.is_err());
+}
+
+#[cfg(test)]
+#[test]
+fn test_slippage_to_bps() {
+    assert_eq!(slippage_to_bps(0.5), Some(50));
+    assert_eq!(slippage_to_bps(50.0), Some(5000));
+    assert_eq!(slippage_to_bps(0.001), Some(1));
+    assert_eq!(slippage_to_bps(0.0), None);
+    assert_eq!(slippage_to_bps(-1.0), None);
+    assert_eq!(slippage_to_bps(50.01), None);
+    assert_eq!(slippage_to_bps(f64::NAN), None);
+    assert_eq!(slippage_to_bps(1e9), None);
+}
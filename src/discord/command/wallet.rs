@@ -0,0 +1,96 @@
+use async_trait::async_trait;
+use std::str::FromStr;
+
+use crate::{
+    catboard::wallet::{fetch_portfolio, Portfolio},
+    discord::{
+        command::{
+            definition::ApplicationCommandOption, CommandContext, CommandOptions, SlashCommand,
+        },
+        interaction::{Embed, InteractionResponse},
+    },
+    solana::{pubkey::Pubkey, web3::Cluster},
+};
+
+const EMBED_COLOR: u32 = 0x8400ff;
+const TOP_HOLDINGS: usize = 10;
+
+pub(crate) struct Wallet;
+
+#[async_trait(?Send)]
+impl SlashCommand for Wallet {
+    fn name(&self) -> &'static str {
+        "wallet"
+    }
+
+    fn description(&self) -> &'static str {
+        "Show SOL and token balances of a wallet valued in USD."
+    }
+
+    fn options(&self) -> Vec<ApplicationCommandOption> {
+        vec![ApplicationCommandOption::string("address", "Wallet address.").required()]
+    }
+
+    fn examples(&self) -> Vec<&'static str> {
+        vec!["/wallet address:8szGkuLTAux9XMgZ2vtY39jVSowEcpBfFfD8hXSEqdGC"]
+    }
+
+    async fn handle(
+        &self,
+        ctx: &CommandContext<'_>,
+        options: &CommandOptions<'_>,
+    ) -> InteractionResponse {
+        let owner = match options
+            .string("address")
+            .map(|a| Pubkey::from_str(a.trim()))
+        {
+            Some(Ok(owner)) => owner,
+            Some(Err(error)) => {
+                return InteractionResponse::ephemeral(format!("😱 Sorry! {}", error))
+            }
+            None => return InteractionResponse::ephemeral("Usage: `/wallet address`".to_string()),
+        };
+
+        match fetch_portfolio(ctx.bindings, ctx.endpoints, &Cluster::Devnet, &owner).await {
+            Ok(portfolio) => InteractionResponse::message(String::new())
+                .with_embeds(vec![portfolio_embed(&owner, &portfolio)]),
+            Err(error) => InteractionResponse::ephemeral(format!("😱 Sorry! {}", error)),
+        }
+    }
+}
+
+fn portfolio_embed(owner: &Pubkey, portfolio: &Portfolio) -> Embed {
+    let embed = Embed::new("👛 Wallet")
+        .description(&format!(
+            "`{}`\nTotal `${:.2}`",
+            owner,
+            portfolio.total_usd()
+        ))
+        .url(&format!("https://solscan.io/account/{}", owner))
+        .color(EMBED_COLOR)
+        .footer(&format!(
+            "Balances mainnet-beta · prices Pyth {}",
+            Cluster::Devnet
+        ));
+
+    let mut embed = portfolio
+        .holdings
+        .iter()
+        .take(TOP_HOLDINGS)
+        .fold(embed, |embed, holding| {
+            let value = match holding.usd {
+                Some(usd) => format!("`{:.4}` ≈ `${:.2}`", holding.amount, usd),
+                None => format!("`{:.4}`", holding.amount),
+            };
+            embed.field(&holding.symbol, &value, true)
+        });
+    if portfolio.holdings.len() > TOP_HOLDINGS {
+        embed = embed.field(
+            "…",
+            &format!("{} more", portfolio.holdings.len() - TOP_HOLDINGS),
+            true,
+        );
+    }
+
+    embed
+}
@@ -0,0 +1,123 @@
+use async_trait::async_trait;
+
+use crate::{
+    catboard::watchlist::{
+        add_to_watchlist, fetch_watchlist_prices, get_watchlist, remove_from_watchlist, to_usd,
+    },
+    discord::{
+        command::{
+            definition::ApplicationCommandOption, CommandContext, CommandOptions, SlashCommand,
+        },
+        interaction::{Embed, InteractionResponse},
+    },
+    solana::web3::Cluster,
+};
+
+const EMBED_COLOR: u32 = 0x8400ff;
+
+pub(crate) struct Watchlist;
+
+#[async_trait(?Send)]
+impl SlashCommand for Watchlist {
+    fn name(&self) -> &'static str {
+        "watchlist"
+    }
+
+    fn description(&self) -> &'static str {
+        "Keep a list of symbols and see all their prices at once."
+    }
+
+    fn options(&self) -> Vec<ApplicationCommandOption> {
+        vec![
+            ApplicationCommandOption::sub_command("add", "Add a symbol to your watchlist.").option(
+                ApplicationCommandOption::string("symbol", "Symbol to watch, e.g. SOL.").required(),
+            ),
+            ApplicationCommandOption::sub_command("remove", "Remove a symbol from your watchlist.")
+                .option(
+                    ApplicationCommandOption::string("symbol", "Symbol to remove, e.g. SOL.")
+                        .required(),
+                ),
+            ApplicationCommandOption::sub_command("show", "Show prices of your watchlist."),
+        ]
+    }
+
+    fn examples(&self) -> Vec<&'static str> {
+        vec!["/watchlist add symbol:SOL", "/watchlist show"]
+    }
+
+    async fn handle(
+        &self,
+        ctx: &CommandContext<'_>,
+        options: &CommandOptions<'_>,
+    ) -> InteractionResponse {
+        let user_id = match ctx.interaction.invoker() {
+            Some(user) => user.id.as_str(),
+            None => return InteractionResponse::ephemeral("😱 Sorry! Who are you?".to_string()),
+        };
+
+        match options.sub_command() {
+            Some(("add", options)) => match options.string("symbol") {
+                Some(symbol) => match add_to_watchlist(ctx.bindings.users(), user_id, symbol).await
+                {
+                    Ok(symbols) => InteractionResponse::ephemeral(format!(
+                        "👀 Watching `{}`.",
+                        symbols.join("`, `")
+                    )),
+                    Err(error) => InteractionResponse::ephemeral(format!("😱 Sorry! {}", error)),
+                },
+                None => {
+                    InteractionResponse::ephemeral("Usage: `/watchlist add symbol`".to_string())
+                }
+            },
+            Some(("remove", options)) => match options.string("symbol") {
+                Some(symbol) => match remove_from_watchlist(ctx.bindings.users(), user_id, symbol)
+                    .await
+                {
+                    Ok(_) => InteractionResponse::ephemeral(format!(
+                        "🙈 Stopped watching `{}`.",
+                        symbol.to_uppercase()
+                    )),
+                    Err(error) => InteractionResponse::ephemeral(format!("😱 Sorry! {}", error)),
+                },
+                None => {
+                    InteractionResponse::ephemeral("Usage: `/watchlist remove symbol`".to_string())
+                }
+            },
+            Some(("show", _)) => show(ctx, user_id).await,
+            _ => InteractionResponse::ephemeral("Usage: `/watchlist add|remove|show`".to_string()),
+        }
+    }
+}
+
+async fn show(ctx: &CommandContext<'_>, user_id: &str) -> InteractionResponse {
+    let symbols = match get_watchlist(ctx.bindings.users(), user_id).await {
+        Ok(symbols) => symbols,
+        Err(error) => return InteractionResponse::ephemeral(format!("😱 Sorry! {}", error)),
+    };
+    if symbols.is_empty() {
+        return InteractionResponse::ephemeral(
+            "👀 Your watchlist is empty, try `/watchlist add`.".to_string(),
+        );
+    }
+
+    let prices =
+        match fetch_watchlist_prices(ctx.bindings, ctx.endpoints, &Cluster::Devnet, &symbols).await
+        {
+            Ok(prices) => prices,
+            Err(error) => return InteractionResponse::ephemeral(format!("😱 Sorry! {}", error)),
+        };
+    let embed = prices.iter().fold(
+        Embed::new("👀 Watchlist")
+            .color(EMBED_COLOR)
+            .footer(&format!("Pyth {}", Cluster::Devnet)),
+        |embed, (symbol, price_conf)| {
+            let value = match price_conf.as_ref().map(to_usd) {
+                Some((price, conf)) => format!("`${:.2}` 🎯`±{:.2}`", price, conf),
+                None => "not support".to_string(),
+            };
+            embed.field(symbol, &value, true)
+        },
+    );
+
+    InteractionResponse::message(String::new()).with_embeds(vec![embed])
+}
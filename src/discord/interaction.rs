@@ -1,15 +1,23 @@
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 
-use crate::cloudflare::worker::WorkersKv;
-use crate::discord::command::handle_command;
+use std::str::FromStr;
+
+use crate::discord::command::{
+    handle_command, handle_component, handle_modal_submit, CommandContext,
+};
+use crate::endpoints::Endpoints;
 use crate::error::Error;
+use crate::kv::bindings::Bindings;
+use crate::task::Task;
 
 #[derive(Deserialize_repr)]
 #[repr(u8)]
 enum InteractionType {
     Ping = 1,
     ApplicationCommand = 2,
+    MessageComponent = 3,
+    ModalSubmit = 5,
 }
 
 #[allow(dead_code)]
@@ -21,11 +29,35 @@ pub(crate) enum InteractionResponseType {
     ChannelMessage = 3,
     ChannelMessageWithSource = 4,
     ACKWithSource = 5,
+    DeferredUpdateMessage = 6,
+    UpdateMessage = 7,
+    Modal = 9,
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize_repr, Serialize_repr)]
+#[repr(u8)]
+pub(crate) enum ApplicationCommandOptionType {
+    SubCommand = 1,
+    SubCommandGroup = 2,
+    String = 3,
+    Integer = 4,
+    Boolean = 5,
+    User = 6,
+    Channel = 7,
+    Role = 8,
+    Mentionable = 9,
+    Number = 10,
+    Attachment = 11,
 }
 
 #[derive(Deserialize)]
 pub(crate) struct ApplicationCommandInteractionDataOptions {
-    pub(crate) value: String,
+    pub(crate) name: String,
+    #[serde(rename = "type")]
+    pub(crate) ty: ApplicationCommandOptionType,
+    pub(crate) value: Option<serde_json::Value>,
+    pub(crate) options: Option<Vec<ApplicationCommandInteractionDataOptions>>,
 }
 
 #[derive(Deserialize)]
@@ -34,43 +66,687 @@ pub(crate) struct ApplicationCommandInteractionData {
     pub(crate) options: Option<Vec<ApplicationCommandInteractionDataOptions>>,
 }
 
-#[derive(Serialize)]
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize_repr, Serialize_repr)]
+#[repr(u8)]
+pub(crate) enum ComponentType {
+    #[default]
+    ActionRow = 1,
+    Button = 2,
+    SelectMenu = 3,
+    TextInput = 4,
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize_repr, Serialize_repr)]
+#[repr(u8)]
+pub(crate) enum ButtonStyle {
+    Primary = 1,
+    Secondary = 2,
+    Success = 3,
+    Danger = 4,
+    Link = 5,
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize_repr, Serialize_repr)]
+#[repr(u8)]
+pub(crate) enum TextInputStyle {
+    Short = 1,
+    Paragraph = 2,
+}
+
+/// Message component, either sent with a response or received back from a modal submit.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub(crate) struct Component {
+    #[serde(rename = "type")]
+    pub(crate) ty: ComponentType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) custom_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) style: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) placeholder: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) required: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) min_length: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) max_length: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) components: Option<Vec<Component>>,
+}
+
+#[allow(dead_code)]
+impl Component {
+    pub(crate) fn action_row(components: Vec<Component>) -> Self {
+        Component {
+            ty: ComponentType::ActionRow,
+            components: Some(components),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn button(custom_id: &str, label: &str, style: ButtonStyle) -> Self {
+        Component {
+            ty: ComponentType::Button,
+            custom_id: Some(custom_id.to_string()),
+            label: Some(label.to_string()),
+            style: Some(style as u8),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn link_button(url: &str, label: &str) -> Self {
+        Component {
+            ty: ComponentType::Button,
+            url: Some(url.to_string()),
+            label: Some(label.to_string()),
+            style: Some(ButtonStyle::Link as u8),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn text_input(custom_id: &str, label: &str, style: TextInputStyle) -> Self {
+        Component {
+            ty: ComponentType::TextInput,
+            custom_id: Some(custom_id.to_string()),
+            label: Some(label.to_string()),
+            style: Some(style as u8),
+            required: Some(true),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn placeholder(mut self, placeholder: &str) -> Self {
+        self.placeholder = Some(placeholder.to_string());
+        self
+    }
+
+    pub(crate) fn max_length(mut self, max_length: u16) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct MessageComponentInteractionData {
+    pub(crate) custom_id: String,
+    #[allow(dead_code)]
+    pub(crate) component_type: ComponentType,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ModalSubmitInteractionData {
+    pub(crate) custom_id: String,
+    pub(crate) components: Vec<Component>,
+}
+
+impl ModalSubmitInteractionData {
+    /// Submitted value of the text input with `custom_id`.
+    pub(crate) fn value(&self, custom_id: &str) -> Option<&str> {
+        self.components
+            .iter()
+            .flat_map(|row| row.components.iter().flatten())
+            .find(|input| input.custom_id.as_deref() == Some(custom_id))
+            .and_then(|input| input.value.as_deref())
+    }
+
+    /// Submitted value of the text input with `custom_id`, parsed as `T`.
+    pub(crate) fn parse<T: FromStr>(&self, custom_id: &str) -> Result<T, Error> {
+        let value = self
+            .value(custom_id)
+            .ok_or_else(|| Error::InvalidPayload(format!("{} not found", custom_id)))?;
+
+        value
+            .trim()
+            .parse()
+            .map_err(|_| Error::InvalidPayload(format!("{} is not valid: {}", custom_id, value)))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+#[serde(transparent)]
+pub(crate) struct MessageFlags(u64);
+
+#[allow(dead_code)]
+impl MessageFlags {
+    pub(crate) const SUPPRESS_EMBEDS: MessageFlags = MessageFlags(1 << 2);
+    pub(crate) const EPHEMERAL: MessageFlags = MessageFlags(1 << 6);
+
+    pub(crate) fn contains(&self, other: MessageFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl std::ops::BitOr for MessageFlags {
+    type Output = MessageFlags;
+
+    fn bitor(self, other: MessageFlags) -> MessageFlags {
+        MessageFlags(self.0 | other.0)
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub(crate) struct AllowedMentions {
+    pub(crate) parse: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) users: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) roles: Vec<String>,
+}
+
+impl AllowedMentions {
+    /// Don't ping anyone, content may echo user input like `@everyone`.
+    pub(crate) fn none() -> Self {
+        AllowedMentions::default()
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub(crate) struct EmbedField {
+    pub(crate) name: String,
+    pub(crate) value: String,
+    pub(crate) inline: bool,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub(crate) struct EmbedFooter {
+    pub(crate) text: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub(crate) struct Embed {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) color: Option<u32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) fields: Vec<EmbedField>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) footer: Option<EmbedFooter>,
+    /// ISO8601, shown in the user's own timezone.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) timestamp: Option<String>,
+}
+
+#[allow(dead_code)]
+impl Embed {
+    pub(crate) fn new(title: &str) -> Self {
+        Embed {
+            title: Some(title.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    pub(crate) fn url(mut self, url: &str) -> Self {
+        self.url = Some(url.to_string());
+        self
+    }
+
+    pub(crate) fn color(mut self, color: u32) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub(crate) fn field(mut self, name: &str, value: &str, inline: bool) -> Self {
+        self.fields.push(EmbedField {
+            name: name.to_string(),
+            value: value.to_string(),
+            inline,
+        });
+        self
+    }
+
+    pub(crate) fn footer(mut self, text: &str) -> Self {
+        self.footer = Some(EmbedFooter {
+            text: text.to_string(),
+        });
+        self
+    }
+
+    pub(crate) fn timestamp(mut self, timestamp: &str) -> Self {
+        self.timestamp = Some(timestamp.to_string());
+        self
+    }
+}
+
+#[derive(Default, Serialize)]
 pub(crate) struct InteractionApplicationCommandCallbackData {
     pub(crate) content: String,
-    pub(crate) embeds: Option<Vec<String>>,
+    pub(crate) embeds: Option<Vec<Embed>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) components: Option<Vec<Component>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) tts: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) flags: Option<MessageFlags>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) allowed_mentions: Option<AllowedMentions>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct InteractionModalCallbackData {
+    pub(crate) custom_id: String,
+    pub(crate) title: String,
+    pub(crate) components: Vec<Component>,
+}
+
+/// Data of a deferred response, only flags apply and the follow-up inherits them.
+#[derive(Default, Serialize)]
+pub(crate) struct InteractionDeferredCallbackData {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) flags: Option<MessageFlags>,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+pub(crate) enum InteractionCallbackData {
+    Message(InteractionApplicationCommandCallbackData),
+    Modal(InteractionModalCallbackData),
+    Deferred(InteractionDeferredCallbackData),
+}
+
+impl From<InteractionApplicationCommandCallbackData> for InteractionCallbackData {
+    fn from(data: InteractionApplicationCommandCallbackData) -> Self {
+        InteractionCallbackData::Message(data)
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct User {
+    pub(crate) id: String,
+    pub(crate) username: String,
+    #[serde(default)]
+    pub(crate) discriminator: Option<String>,
+    #[serde(default)]
+    pub(crate) global_name: Option<String>,
+    #[serde(default)]
+    pub(crate) avatar: Option<String>,
+    #[serde(default)]
+    pub(crate) bot: bool,
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct GuildMember {
+    pub(crate) user: Option<User>,
+    #[serde(default)]
+    pub(crate) nick: Option<String>,
+    #[serde(default)]
+    pub(crate) roles: Vec<String>,
+    #[serde(default)]
+    pub(crate) permissions: Option<String>,
+    #[serde(default)]
+    pub(crate) joined_at: Option<String>,
 }
 
+#[allow(dead_code)]
 #[derive(Deserialize)]
 pub(crate) struct Interaction {
     #[serde(rename = "type")]
     ty: InteractionType,
-    data: Option<ApplicationCommandInteractionData>,
+    data: Option<serde_json::Value>,
+    #[serde(default)]
+    pub(crate) id: String,
+    #[serde(default)]
+    pub(crate) application_id: String,
+    #[serde(default)]
+    pub(crate) token: String,
+    /// Set when invoked in a guild.
+    pub(crate) member: Option<GuildMember>,
+    /// Set when invoked in a DM.
+    pub(crate) user: Option<User>,
+    pub(crate) guild_id: Option<String>,
+    pub(crate) channel_id: Option<String>,
+    pub(crate) locale: Option<String>,
+    pub(crate) guild_locale: Option<String>,
+    /// Bitwise set of permissions the app has in the source channel.
+    pub(crate) app_permissions: Option<String>,
 }
 
+#[allow(dead_code)]
 impl Interaction {
-    fn data(&self) -> Result<&ApplicationCommandInteractionData, Error> {
-        Ok(self
+    /// User who invoked the interaction, in a guild or a DM.
+    pub(crate) fn invoker(&self) -> Option<&User> {
+        self.member
+            .as_ref()
+            .and_then(|member| member.user.as_ref())
+            .or(self.user.as_ref())
+    }
+
+    fn data<T: DeserializeOwned>(&self) -> Result<T, Error> {
+        let data = self
             .data
             .as_ref()
-            .ok_or_else(|| Error::InvalidPayload("data not found".to_string()))?)
+            .ok_or_else(|| Error::InvalidPayload("data not found".to_string()))?;
+
+        serde_json::from_value(data.clone()).map_err(Error::JsonFailed)
     }
 }
 
+#[derive(Clone, Debug)]
+pub(crate) struct Attachment {
+    pub(crate) filename: String,
+    pub(crate) content_type: String,
+    pub(crate) bytes: Vec<u8>,
+}
+
+/// Message posted through the interaction webhook after a deferred response, which decides
+/// whether it's ephemeral.
+#[derive(Clone, Debug)]
+pub(crate) struct FollowUpMessage {
+    pub(crate) application_id: String,
+    pub(crate) interaction_token: String,
+    pub(crate) content: String,
+    pub(crate) attachments: Vec<Attachment>,
+    pub(crate) flags: Option<MessageFlags>,
+}
+
 #[derive(Serialize)]
 pub(crate) struct InteractionResponse {
     #[serde(rename = "type")]
     pub(crate) ty: InteractionResponseType,
-    pub(crate) data: Option<InteractionApplicationCommandCallbackData>,
+    pub(crate) data: Option<InteractionCallbackData>,
+    /// Run once the response is returned.
+    #[serde(skip)]
+    pub(crate) tasks: Vec<Task>,
+}
+
+#[allow(dead_code)]
+impl InteractionResponse {
+    /// Reply visible to everyone in the channel.
+    pub(crate) fn message(content: String) -> Self {
+        InteractionResponse {
+            ty: InteractionResponseType::ChannelMessageWithSource,
+            data: Some(
+                InteractionApplicationCommandCallbackData {
+                    content,
+                    allowed_mentions: Some(AllowedMentions::none()),
+                    ..Default::default()
+                }
+                .into(),
+            ),
+            tasks: Vec::new(),
+        }
+    }
+
+    /// Show "thinking..." now, a task posts the `FollowUpMessage` when it's ready.
+    pub(crate) fn deferred() -> Self {
+        InteractionResponse {
+            ty: InteractionResponseType::ACKWithSource,
+            data: Some(InteractionCallbackData::Deferred(Default::default())),
+            tasks: Vec::new(),
+        }
+    }
+
+    /// Reply only the invoking user can see, used for errors and usage hints.
+    pub(crate) fn ephemeral(content: String) -> Self {
+        Self::message(content).with_flags(MessageFlags::EPHEMERAL)
+    }
+
+    fn message_data(&mut self) -> Option<&mut InteractionApplicationCommandCallbackData> {
+        match self.data.as_mut() {
+            Some(InteractionCallbackData::Message(data)) => Some(data),
+            _ => None,
+        }
+    }
+
+    fn flags(&self) -> Option<MessageFlags> {
+        match self.data.as_ref() {
+            Some(InteractionCallbackData::Message(data)) => data.flags,
+            Some(InteractionCallbackData::Deferred(data)) => data.flags,
+            _ => None,
+        }
+    }
+
+    pub(crate) fn is_ephemeral(&self) -> bool {
+        matches!(self.flags(), Some(flags) if flags.contains(MessageFlags::EPHEMERAL))
+    }
+
+    pub(crate) fn with_flags(mut self, flags: MessageFlags) -> Self {
+        match self.data.as_mut() {
+            Some(InteractionCallbackData::Message(data)) => {
+                data.flags = Some(data.flags.unwrap_or_default() | flags)
+            }
+            Some(InteractionCallbackData::Deferred(data)) => {
+                data.flags = Some(data.flags.unwrap_or_default() | flags)
+            }
+            _ => {}
+        }
+        self
+    }
+
+    pub(crate) fn with_components(mut self, components: Vec<Component>) -> Self {
+        if let Some(data) = self.message_data() {
+            data.components = Some(components);
+        }
+        self
+    }
+
+    pub(crate) fn with_embeds(mut self, embeds: Vec<Embed>) -> Self {
+        if let Some(data) = self.message_data() {
+            data.embeds = Some(embeds);
+        }
+        self
+    }
+
+    pub(crate) fn with_tts(mut self, tts: bool) -> Self {
+        if let Some(data) = self.message_data() {
+            data.tts = Some(tts);
+        }
+        self
+    }
+
+    /// Run `task` in the background after replying.
+    pub(crate) fn with_task(mut self, task: Task) -> Self {
+        self.tasks.push(task);
+        self
+    }
+
+    /// Open a modal whose submit comes back with the same `custom_id`.
+    pub(crate) fn modal(custom_id: &str, title: &str, inputs: Vec<Component>) -> Self {
+        InteractionResponse {
+            ty: InteractionResponseType::Modal,
+            data: Some(InteractionCallbackData::Modal(
+                InteractionModalCallbackData {
+                    custom_id: custom_id.to_string(),
+                    title: title.to_string(),
+                    // Each text input needs its own row.
+                    components: inputs
+                        .into_iter()
+                        .map(|input| Component::action_row(vec![input]))
+                        .collect(),
+                },
+            )),
+            tasks: Vec::new(),
+        }
+    }
 }
 
 impl Interaction {
-    pub(crate) async fn perform(&self, kv: &WorkersKv) -> Result<InteractionResponse, Error> {
+    pub(crate) async fn perform(
+        &self,
+        bindings: &Bindings,
+        endpoints: &Endpoints,
+    ) -> Result<InteractionResponse, Error> {
+        let ctx = CommandContext {
+            bindings,
+            endpoints,
+            interaction: self,
+        };
+
         Ok(match self.ty {
             InteractionType::Ping => InteractionResponse {
                 ty: InteractionResponseType::Pong,
                 data: None,
+                tasks: Vec::new(),
             },
-            InteractionType::ApplicationCommand => handle_command(self.data()?, kv).await,
+            InteractionType::ApplicationCommand => handle_command(&ctx, &self.data()?).await,
+            InteractionType::MessageComponent => handle_component(&ctx, &self.data()?).await,
+            InteractionType::ModalSubmit => handle_modal_submit(&ctx, &self.data()?).await,
         })
     }
 }
+
+#[cfg(test)]
+#[test]
+fn test_modal_submit_values() {
+    let data: ModalSubmitInteractionData = serde_json::from_str(
+        r#"{"custom_id":"price:alert:SOL","components":[
+            {"type":1,"components":[{"type":4,"custom_id":"direction","value":"above"}]},
+            {"type":1,"components":[{"type":4,"custom_id":"price","value":" 101.5 "}]}
+        ]}"#,
+    )
+    .unwrap();
+
+    assert_eq!(data.value("direction"), Some("above"));
+    assert_eq!(data.parse::<f64>("price").unwrap(), 101.5);
+    assert!(data.parse::<f64>("direction").is_err());
+    assert!(data.value("missing").is_none());
+}
+
+#[cfg(test)]
+#[test]
+fn test_modal_response_serialize() {
+    let response = InteractionResponse::modal(
+        "price:alert:SOL",
+        "Create price alert",
+        vec![Component::text_input(
+            "price",
+            "Target price",
+            TextInputStyle::Short,
+        )],
+    );
+
+    assert_eq!(
+        serde_json::to_value(&response).unwrap(),
+        serde_json::json!({
+            "type": 9,
+            "data": {
+                "custom_id": "price:alert:SOL",
+                "title": "Create price alert",
+                "components": [{
+                    "type": 1,
+                    "components": [{
+                        "type": 4,
+                        "custom_id": "price",
+                        "style": 1,
+                        "label": "Target price",
+                        "required": true,
+                    }],
+                }],
+            },
+        })
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_ephemeral_message_serialize() {
+    let response = InteractionResponse::ephemeral("Oops".to_string())
+        .with_flags(MessageFlags::SUPPRESS_EMBEDS);
+
+    assert!(response.is_ephemeral());
+    assert_eq!(
+        serde_json::to_value(&response).unwrap(),
+        serde_json::json!({
+            "type": 4,
+            "data": {
+                "content": "Oops",
+                "embeds": null,
+                "flags": 68,
+                "allowed_mentions": { "parse": [] },
+            },
+        })
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_interaction_invoker() {
+    let guild: Interaction = serde_json::from_str(
+        r#"{"type":2,"id":"1","application_id":"2","token":"t","guild_id":"3","channel_id":"4",
+            "locale":"en-US","guild_locale":"th","app_permissions":"2048",
+            "member":{"user":{"id":"5","username":"cat"},"nick":"kitty","roles":["6"]}}"#,
+    )
+    .unwrap();
+    let dm: Interaction = serde_json::from_str(
+        r#"{"type":2,"id":"1","application_id":"2","token":"t","channel_id":"4",
+            "user":{"id":"7","username":"dog"}}"#,
+    )
+    .unwrap();
+
+    assert_eq!(guild.invoker().unwrap().id, "5");
+    assert_eq!(
+        guild.member.as_ref().unwrap().nick.as_deref(),
+        Some("kitty")
+    );
+    assert_eq!(guild.guild_id.as_deref(), Some("3"));
+    assert_eq!(guild.app_permissions.as_deref(), Some("2048"));
+    assert_eq!(dm.invoker().unwrap().username, "dog");
+    assert_eq!(dm.guild_id, None);
+}
+
+#[cfg(test)]
+#[test]
+fn test_embed_serialize() {
+    let response =
+        InteractionResponse::message(String::new()).with_embeds(vec![Embed::new("👀 Watchlist")
+            .color(0x8400ff)
+            .field("SOL", "`$100.00`", true)]);
+
+    assert_eq!(
+        serde_json::to_value(&response).unwrap()["data"]["embeds"],
+        serde_json::json!([{
+            "title": "👀 Watchlist",
+            "color": 0x8400ff,
+            "fields": [{ "name": "SOL", "value": "`$100.00`", "inline": true }],
+        }])
+    );
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_perform_against_memory_kv() {
+    use crate::catboard::watchlist::get_watchlist;
+
+    let bindings = Bindings::in_memory();
+    let add: Interaction = serde_json::from_str(
+        r#"{"type":2,"id":"1","application_id":"2","token":"t","channel_id":"4",
+            "user":{"id":"7","username":"dog"},
+            "data":{"id":"8","name":"watchlist","options":[
+                {"name":"add","type":1,"options":[{"name":"symbol","type":3,"value":"sol"}]}
+            ]}}"#,
+    )
+    .unwrap();
+
+    let endpoints = Endpoints::default();
+    add.perform(&bindings, &endpoints).await.unwrap();
+
+    assert_eq!(
+        get_watchlist(bindings.users(), "7").await.unwrap(),
+        vec!["SOL"]
+    );
+    let response = serde_json::to_value(add.perform(&bindings, &endpoints).await.unwrap()).unwrap();
+    assert!(response["data"]["content"]
+        .as_str()
+        .unwrap()
+        .contains("already watched"));
+}
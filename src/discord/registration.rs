@@ -0,0 +1,173 @@
+use std::fmt;
+
+use crate::{
+    discord::{command::definition::ApplicationCommand, rest::DiscordClient},
+    error::Error,
+};
+
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct CommandDiff {
+    pub(crate) added: Vec<String>,
+    pub(crate) changed: Vec<String>,
+    pub(crate) removed: Vec<String>,
+}
+
+impl CommandDiff {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl fmt::Display for CommandDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "Commands are up to date.");
+        }
+
+        for name in self.added.iter() {
+            writeln!(f, "+ /{}", name)?;
+        }
+        for name in self.changed.iter() {
+            writeln!(f, "~ /{}", name)?;
+        }
+        for name in self.removed.iter() {
+            writeln!(f, "- /{}", name)?;
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn diff_commands(
+    local: &[ApplicationCommand],
+    remote: &[ApplicationCommand],
+) -> CommandDiff {
+    let mut diff = CommandDiff::default();
+
+    for command in local.iter() {
+        match remote.iter().find(|r| r.name == command.name) {
+            None => diff.added.push(command.name.clone()),
+            Some(registered) if registered != command => diff.changed.push(command.name.clone()),
+            Some(_) => {}
+        }
+    }
+
+    for registered in remote.iter() {
+        if !local.iter().any(|c| c.name == registered.name) {
+            diff.removed.push(registered.name.clone());
+        }
+    }
+
+    diff
+}
+
+/// Compare `commands` with what Discord has registered and overwrite them when they differ.
+///
+/// With `dry_run` the diff is only computed, nothing is written.
+pub(crate) async fn sync_commands(
+    client: &DiscordClient,
+    application_id: &str,
+    guild_id: Option<&str>,
+    commands: &[ApplicationCommand],
+    dry_run: bool,
+) -> Result<CommandDiff, Error> {
+    let registered = client.get_commands(application_id, guild_id).await?;
+    let diff = diff_commands(commands, &registered);
+
+    if !dry_run && !diff.is_empty() {
+        client
+            .bulk_overwrite_commands(application_id, guild_id, commands)
+            .await?;
+    }
+
+    Ok(diff)
+}
+
+#[cfg(test)]
+use crate::{discord::command::CommandRegistry, testing::MockServer};
+
+#[cfg(test)]
+#[test]
+fn test_diff_commands() {
+    let local = CommandRegistry::default().definitions();
+    let mut remote = local.clone();
+    remote[1].description = "Old description.".to_string();
+    remote.push(ApplicationCommand {
+        name: "legacy".to_string(),
+        description: "Gone.".to_string(),
+        options: Vec::new(),
+    });
+    remote.remove(0);
+
+    let diff = diff_commands(&local, &remote);
+
+    assert_eq!(diff.added, vec!["hello"]);
+    assert_eq!(diff.changed, vec!["price"]);
+    assert_eq!(diff.removed, vec!["legacy"]);
+    assert_eq!(diff.to_string(), "+ /hello\n~ /price\n- /legacy\n");
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_sync_commands_dry_run() {
+    let server = MockServer::start(|_request| (200, "[]".to_string())).await;
+    let client = DiscordClient::with_api_url(&server.url(), "token");
+    let commands = CommandRegistry::default().definitions();
+
+    let diff = sync_commands(&client, "app", None, &commands, true)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        diff.added,
+        vec![
+            "hello",
+            "price",
+            "chart",
+            "indicators",
+            "convert",
+            "alert",
+            "watchlist",
+            "swap_quote",
+            "stake",
+            "lend",
+            "wallet",
+            "help",
+            "about"
+        ]
+    );
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "GET");
+    assert_eq!(requests[0].path, "/applications/app/commands");
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_sync_commands_overwrites_guild_commands() {
+    let server = MockServer::start(|request| match request.method.as_str() {
+        "PUT" => (200, request.body.clone()),
+        _ => (
+            200,
+            r#"[{"id":"1","name":"legacy","description":"Gone."}]"#.to_string(),
+        ),
+    })
+    .await;
+    let client = DiscordClient::with_api_url(&server.url(), "token");
+    let commands = CommandRegistry::default().definitions();
+
+    let diff = sync_commands(&client, "app", Some("guild"), &commands, false)
+        .await
+        .unwrap();
+
+    assert_eq!(diff.removed, vec!["legacy"]);
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[1].method, "PUT");
+    assert_eq!(requests[1].path, "/applications/app/guilds/guild/commands");
+    assert_eq!(
+        requests[1].headers.get("authorization").unwrap(),
+        "Bot token"
+    );
+    let sent: Vec<ApplicationCommand> = serde_json::from_str(&requests[1].body).unwrap();
+    assert_eq!(sent, commands);
+}
@@ -0,0 +1,214 @@
+use serde_json::json;
+
+use crate::{
+    discord::{
+        command::definition::ApplicationCommand,
+        interaction::{Attachment, FollowUpMessage},
+    },
+    error::Error,
+};
+
+const DISCORD_API_URL: &str = "https://discord.com/api/v10";
+
+pub(crate) struct DiscordClient {
+    client: reqwest::Client,
+    api_url: String,
+    token: String,
+}
+
+#[allow(dead_code)]
+impl DiscordClient {
+    /// Client authorized with a bot `token`.
+    pub(crate) fn new(token: &str) -> Self {
+        Self::with_api_url(DISCORD_API_URL, token)
+    }
+
+    pub(crate) fn with_api_url(api_url: &str, token: &str) -> Self {
+        DiscordClient {
+            client: reqwest::Client::new(),
+            api_url: api_url.trim_end_matches('/').to_string(),
+            token: token.to_string(),
+        }
+    }
+
+    fn commands_url(&self, application_id: &str, guild_id: Option<&str>) -> String {
+        match guild_id {
+            Some(guild_id) => format!(
+                "{}/applications/{}/guilds/{}/commands",
+                self.api_url, application_id, guild_id
+            ),
+            None => format!("{}/applications/{}/commands", self.api_url, application_id),
+        }
+    }
+
+    fn authorization(&self) -> String {
+        format!("Bot {}", self.token)
+    }
+
+    /// Get global commands, or guild commands when `guild_id` is given.
+    pub(crate) async fn get_commands(
+        &self,
+        application_id: &str,
+        guild_id: Option<&str>,
+    ) -> Result<Vec<ApplicationCommand>, Error> {
+        let response = self
+            .client
+            .get(self.commands_url(application_id, guild_id))
+            .header("Authorization", self.authorization())
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Replace every registered command with `commands` in one request.
+    pub(crate) async fn bulk_overwrite_commands(
+        &self,
+        application_id: &str,
+        guild_id: Option<&str>,
+        commands: &[ApplicationCommand],
+    ) -> Result<Vec<ApplicationCommand>, Error> {
+        let response = self
+            .client
+            .put(self.commands_url(application_id, guild_id))
+            .header("Authorization", self.authorization())
+            .json(commands)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Post `message` through the webhook of the interaction it follows up.
+    ///
+    /// Interaction webhooks are authorized by the interaction token, the bot token isn't used.
+    pub(crate) async fn create_followup_message(
+        &self,
+        message: &FollowUpMessage,
+    ) -> Result<(), Error> {
+        let url = format!(
+            "{}/webhooks/{}/{}",
+            self.api_url, message.application_id, message.interaction_token
+        );
+        let (boundary, body) = multipart_body(&followup_payload(message), &message.attachments);
+
+        self.client
+            .post(url)
+            .header(
+                "Content-Type",
+                format!("multipart/form-data; boundary={}", boundary),
+            )
+            .body(body)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Post `content` to a channel as the bot, only pinging `user_id` when given.
+    pub(crate) async fn create_message(
+        &self,
+        channel_id: &str,
+        content: &str,
+        user_id: Option<&str>,
+    ) -> Result<(), Error> {
+        let url = format!("{}/channels/{}/messages", self.api_url, channel_id);
+        let users: Vec<&str> = user_id.into_iter().collect();
+
+        self.client
+            .post(url)
+            .header("Authorization", self.authorization())
+            .json(&json!({
+                "content": content,
+                "allowed_mentions": { "parse": [], "users": users },
+            }))
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+}
+
+fn followup_payload(message: &FollowUpMessage) -> serde_json::Value {
+    let attachments: Vec<serde_json::Value> = message
+        .attachments
+        .iter()
+        .enumerate()
+        .map(|(id, attachment)| json!({ "id": id, "filename": attachment.filename }))
+        .collect();
+
+    let mut payload = json!({
+        "content": message.content,
+        "attachments": attachments,
+        "allowed_mentions": { "parse": [] },
+    });
+    if let Some(flags) = message.flags {
+        payload["flags"] = json!(flags);
+    }
+    payload
+}
+
+/// Build a `multipart/form-data` body with `payload_json` and one `files[n]` part per attachment.
+fn multipart_body(payload: &serde_json::Value, attachments: &[Attachment]) -> (String, Vec<u8>) {
+    let boundary = "catboard-boundary-7MA4YWxkTrZu0gW".to_string();
+    let mut body = Vec::new();
+
+    body.extend_from_slice(
+        format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"payload_json\"\r\nContent-Type: application/json\r\n\r\n{}\r\n",
+            boundary, payload
+        )
+        .as_bytes(),
+    );
+    for (id, attachment) in attachments.iter().enumerate() {
+        body.extend_from_slice(
+            format!(
+                "--{}\r\nContent-Disposition: form-data; name=\"files[{}]\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\n",
+                boundary, id, attachment.filename, attachment.content_type
+            )
+            .as_bytes(),
+        );
+        body.extend_from_slice(&attachment.bytes);
+        body.extend_from_slice(b"\r\n");
+    }
+    body.extend_from_slice(format!("--{}--\r\n", boundary).as_bytes());
+
+    (boundary, body)
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_create_followup_message_multipart() {
+    use crate::testing::MockServer;
+
+    let server = MockServer::start(|_request| (200, "{}".to_string())).await;
+    let client = DiscordClient::with_api_url(&server.url(), "");
+    let message = FollowUpMessage {
+        application_id: "app".to_string(),
+        interaction_token: "token".to_string(),
+        content: "📈 `SOL`".to_string(),
+        attachments: vec![Attachment {
+            filename: "chart.png".to_string(),
+            content_type: "image/png".to_string(),
+            bytes: b"PNG".to_vec(),
+        }],
+        flags: None,
+    };
+
+    client.create_followup_message(&message).await.unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests[0].path, "/webhooks/app/token");
+    assert!(requests[0].headers["content-type"].starts_with("multipart/form-data; boundary="));
+    assert!(requests[0]
+        .body
+        .contains(r#"name="files[0]"; filename="chart.png""#));
+    assert!(requests[0]
+        .body
+        .contains(r#""attachments":[{"filename":"chart.png","id":0}]"#));
+    assert!(requests[0].body.contains("\r\nPNG\r\n"));
+}
@@ -39,12 +39,18 @@ pub(crate) fn verify_signature(
     )?)
 }
 
-#[allow(dead_code)]
+/// Compare every byte whatever the first mismatch, so timing doesn't tell how much of a key matched.
 pub(crate) fn verify_api_key(
     request_api_key: &str,
     api_key: &str,
 ) -> Result<(), VerificationError> {
-    if request_api_key == api_key {
+    let (request_api_key, api_key) = (request_api_key.as_bytes(), api_key.as_bytes());
+    let diff = request_api_key
+        .iter()
+        .zip(api_key.iter())
+        .fold(0, |diff, (a, b)| diff | (a ^ b));
+
+    if !api_key.is_empty() && request_api_key.len() == api_key.len() && diff == 0 {
         Ok(())
     } else {
         Err(VerificationError::InvalidApiKey())
@@ -0,0 +1,67 @@
+//! Where the bot reaches Solana, Discord and Jupiter, the public APIs unless the env points
+//! elsewhere, e.g. `wrangler dev` against a local validator or tests against mock servers.
+
+use std::collections::HashMap;
+
+use crate::{discord::rest::DiscordClient, jupiter::quote::JupiterClient, solana::web3::Cluster};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct Endpoints {
+    /// `SOLANA_RPC_URL`, answering for every cluster.
+    rpc_url: Option<String>,
+    /// `DISCORD_API_URL`.
+    discord_api_url: Option<String>,
+    /// `JUPITER_QUOTE_API_URL`.
+    quote_api_url: Option<String>,
+}
+
+impl Endpoints {
+    pub(crate) fn from_env(env: &HashMap<String, String>) -> Self {
+        Endpoints {
+            rpc_url: env.get("SOLANA_RPC_URL").cloned(),
+            discord_api_url: env.get("DISCORD_API_URL").cloned(),
+            quote_api_url: env.get("JUPITER_QUOTE_API_URL").cloned(),
+        }
+    }
+
+    /// Cluster to send the RPC of `cluster` to. KV namespaces are still picked with `cluster`.
+    pub(crate) fn rpc(&self, cluster: &Cluster) -> Cluster {
+        match &self.rpc_url {
+            Some(url) => Cluster::Custom(url.clone()),
+            None => cluster.clone(),
+        }
+    }
+
+    /// Discord client authorized with a bot `token`, follow-ups need none.
+    pub(crate) fn discord(&self, token: &str) -> DiscordClient {
+        match &self.discord_api_url {
+            Some(api_url) => DiscordClient::with_api_url(api_url, token),
+            None => DiscordClient::new(token),
+        }
+    }
+
+    pub(crate) fn jupiter(&self) -> JupiterClient {
+        match &self.quote_api_url {
+            Some(api_url) => JupiterClient::with_api_url(api_url),
+            None => JupiterClient::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_endpoints_from_env() {
+    let public = Endpoints::from_env(&HashMap::new());
+    assert_eq!(public, Endpoints::default());
+    assert_eq!(public.rpc(&Cluster::MainnetBeta), Cluster::MainnetBeta);
+
+    let env: HashMap<String, String> = [("SOLANA_RPC_URL", "http://127.0.0.1:8899")]
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    let local = Endpoints::from_env(&env);
+    assert_eq!(
+        local.rpc(&Cluster::MainnetBeta),
+        Cluster::Custom("http://127.0.0.1:8899".to_string())
+    );
+}
@@ -14,6 +14,25 @@ pub(crate) enum Error {
     #[error("Invalid payload provided: {0}.")]
     InvalidPayload(String),
 
+    #[error("Not found: {0}.")]
+    NotFound(String),
+
     #[error("Verification failed.")]
     VerificationFailed(VerificationError),
+
+    #[error("Key-value store failed: {0}.")]
+    KvFailed(String),
+
+    #[error("IO failed: {0}.")]
+    IoFailed(#[from] std::io::Error),
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[error("Server failed: {0}.")]
+    ServerFailed(String),
+
+    #[error("Request failed: {0}.")]
+    RequestFailed(#[from] reqwest::Error),
+
+    #[error("Solana RPC failed: {0}.")]
+    RpcFailed(String),
 }
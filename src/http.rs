@@ -4,6 +4,7 @@ use std::collections::HashMap;
 use std::fmt;
 
 use crate::error::Error;
+use crate::task::Task;
 
 #[derive(Deserialize)]
 #[allow(dead_code)]
@@ -22,16 +23,25 @@ impl HttpRequest {
     }
 }
 
-#[derive(Serialize)]
+#[derive(Default, Serialize)]
 pub(crate) struct HttpResponse {
     pub status: u16,
     pub body: String,
+    /// Binary body, sent instead of `body` when set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bytes: Option<Vec<u8>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    /// Run after the response is returned.
+    #[serde(skip)]
+    pub tasks: Vec<Task>,
 }
 
 #[derive(Debug)]
 pub(crate) enum HttpStatus {
     BadRequest = 400,
     Unauthorized = 401,
+    NotFound = 404,
     InternalServerError = 500,
 }
 
@@ -55,6 +65,7 @@ impl From<Error> for HttpError {
                     HttpStatus::BadRequest
                 }
                 Error::VerificationFailed(_) => HttpStatus::Unauthorized,
+                Error::NotFound(_) => HttpStatus::NotFound,
                 _ => HttpStatus::InternalServerError,
             },
             reason: error,
@@ -0,0 +1,2 @@
+pub(crate) mod quote;
+pub(crate) mod token;
@@ -0,0 +1,207 @@
+use serde::Deserialize;
+
+use crate::{
+    error::Error,
+    jupiter::token::{find_token_by_mint, Token},
+};
+
+const JUPITER_QUOTE_API_URL: &str = "https://quote-api.jup.ag/v6";
+const JUPITER_SWAP_URL: &str = "https://jup.ag/swap";
+
+#[allow(dead_code)]
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SwapInfo {
+    pub(crate) amm_key: String,
+    pub(crate) label: String,
+    pub(crate) input_mint: String,
+    pub(crate) output_mint: String,
+    pub(crate) in_amount: String,
+    pub(crate) out_amount: String,
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RoutePlan {
+    pub(crate) swap_info: SwapInfo,
+    pub(crate) percent: u8,
+}
+
+/// Best route of a quote, amounts are raw token units as strings.
+#[allow(dead_code)]
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Quote {
+    pub(crate) input_mint: String,
+    pub(crate) in_amount: String,
+    pub(crate) output_mint: String,
+    pub(crate) out_amount: String,
+    /// Minimum received after slippage.
+    pub(crate) other_amount_threshold: String,
+    pub(crate) slippage_bps: u16,
+    pub(crate) price_impact_pct: String,
+    pub(crate) route_plan: Vec<RoutePlan>,
+}
+
+fn parse_amount(amount: &str) -> Result<u64, Error> {
+    amount
+        .parse()
+        .map_err(|_| Error::InvalidPayload(format!("amount is not valid: {}", amount)))
+}
+
+fn token_symbol(mint: &str) -> String {
+    match find_token_by_mint(mint) {
+        Some(token) => token.symbol.to_string(),
+        None => format!("{}…", mint.chars().take(4).collect::<String>()),
+    }
+}
+
+impl Quote {
+    pub(crate) fn out_amount(&self) -> Result<u64, Error> {
+        parse_amount(&self.out_amount)
+    }
+
+    pub(crate) fn min_out_amount(&self) -> Result<u64, Error> {
+        parse_amount(&self.other_amount_threshold)
+    }
+
+    /// Price impact in percent, Jupiter reports it as a fraction.
+    pub(crate) fn price_impact(&self) -> f64 {
+        self.price_impact_pct.parse::<f64>().unwrap_or_default() * 100.0
+    }
+
+    /// Tokens along the route, e.g. `SOL → USDT → USDC`.
+    pub(crate) fn hops(&self) -> String {
+        let mut mints = vec![self.input_mint.as_str()];
+        for plan in self.route_plan.iter() {
+            if mints.last() != Some(&plan.swap_info.output_mint.as_str()) {
+                mints.push(&plan.swap_info.output_mint);
+            }
+        }
+
+        let symbols: Vec<String> = mints.iter().map(|mint| token_symbol(mint)).collect();
+        symbols.join(" → ")
+    }
+
+    /// AMMs used along the route, e.g. `Whirlpool, Meteora DLMM`.
+    pub(crate) fn venues(&self) -> String {
+        let mut labels: Vec<&str> = Vec::new();
+        for plan in self.route_plan.iter() {
+            if !labels.contains(&plan.swap_info.label.as_str()) {
+                labels.push(&plan.swap_info.label);
+            }
+        }
+        labels.join(", ")
+    }
+}
+
+/// Page to complete a swap in the Jupiter app.
+pub(crate) fn swap_url(input: &Token, output: &Token) -> String {
+    format!("{}/{}-{}", JUPITER_SWAP_URL, input.symbol, output.symbol)
+}
+
+pub(crate) struct JupiterClient {
+    client: reqwest::Client,
+    api_url: String,
+}
+
+impl JupiterClient {
+    pub(crate) fn new() -> Self {
+        Self::with_api_url(JUPITER_QUOTE_API_URL)
+    }
+
+    pub(crate) fn with_api_url(api_url: &str) -> Self {
+        JupiterClient {
+            client: reqwest::Client::new(),
+            api_url: api_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Quote swapping raw `amount` of `input` to `output`.
+    pub(crate) async fn quote(
+        &self,
+        input: &Token,
+        output: &Token,
+        amount: u64,
+        slippage_bps: u16,
+    ) -> Result<Quote, Error> {
+        let response = self
+            .client
+            .get(format!("{}/quote", self.api_url))
+            .query(&[
+                ("inputMint", input.mint.to_string()),
+                ("outputMint", output.mint.to_string()),
+                ("amount", amount.to_string()),
+                ("slippageBps", slippage_bps.to_string()),
+            ])
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(response.json().await?)
+    }
+}
+
+#[cfg(test)]
+use crate::{jupiter::token::find_token_by_symbol, testing::MockServer};
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_quote_from_recorded_response() {
+    let server = MockServer::start(|_request| {
+        (
+            200,
+            include_str!("../../tests/fixtures/jupiter/quote_sol_usdc.json").to_string(),
+        )
+    })
+    .await;
+    let client = JupiterClient::with_api_url(&server.url());
+    let sol = find_token_by_symbol("SOL").unwrap();
+    let usdc = find_token_by_symbol("USDC").unwrap();
+
+    let quote = client.quote(&sol, &usdc, 1_000_000_000, 50).await.unwrap();
+
+    assert_eq!(
+        server.requests()[0].path,
+        format!(
+            "/quote?inputMint={}&outputMint={}&amount=1000000000&slippageBps=50",
+            sol.mint, usdc.mint
+        )
+    );
+    assert_eq!(usdc.to_ui(quote.out_amount().unwrap()), 145.231876);
+    assert_eq!(usdc.to_ui(quote.min_out_amount().unwrap()), 144.505717);
+    assert!((quote.price_impact() - 0.12).abs() < 1e-9);
+    assert_eq!(quote.hops(), "SOL → USDT → USDC");
+    assert_eq!(quote.venues(), "Whirlpool, Meteora DLMM");
+    assert_eq!(swap_url(&sol, &usdc), "https://jup.ag/swap/SOL-USDC");
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_quote_without_route() {
+    let server = MockServer::start(|_request| {
+        (
+            400,
+            r#"{"error":"Could not find any route","errorCode":"COULD_NOT_FIND_ANY_ROUTE"}"#
+                .to_string(),
+        )
+    })
+    .await;
+    let client = JupiterClient::with_api_url(&server.url());
+    let sol = find_token_by_symbol("SOL").unwrap();
+
+    assert!(client.quote(&sol, &sol, 1, 50).await.is_err());
+}
+
+#[cfg(test)]
+#[test]
+fn test_token_symbol_of_unknown_mint() {
+    assert_eq!(
+        token_symbol("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"),
+        "USDC"
+    );
+    assert_eq!(token_symbol("7xKXtg2CW87d"), "7xKX…");
+    assert_eq!(token_symbol("ééééé"), "éééé…");
+    assert_eq!(token_symbol("ab"), "ab…");
+}
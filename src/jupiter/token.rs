@@ -0,0 +1,81 @@
+/// SPL token we know the mint of, Jupiter only speaks mints and raw amounts.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Token {
+    pub(crate) symbol: &'static str,
+    pub(crate) mint: &'static str,
+    pub(crate) decimals: u32,
+}
+
+const TOKENS: [Token; 7] = [
+    Token {
+        symbol: "SOL",
+        mint: "So11111111111111111111111111111111111111112",
+        decimals: 9,
+    },
+    Token {
+        symbol: "USDC",
+        mint: "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
+        decimals: 6,
+    },
+    Token {
+        symbol: "USDT",
+        mint: "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB",
+        decimals: 6,
+    },
+    Token {
+        symbol: "MSOL",
+        mint: "mSoLzYCxHdYgdzU16g5QSh3i5K3z3KZK7ytfqcJm7So",
+        decimals: 9,
+    },
+    Token {
+        symbol: "BTC",
+        mint: "3NZ9JMVBmGAqocybic2c7LQCJScmgsAZ6vQqTDzcqmJh",
+        decimals: 8,
+    },
+    Token {
+        symbol: "ETH",
+        mint: "7vfCXTUXx5WJV5JADk17DUJ4ksgau7utNKj4b963voxs",
+        decimals: 8,
+    },
+    Token {
+        symbol: "JUP",
+        mint: "JUPyiwrYJFskUPiHa7hkeR8VUtAeFoSYbKedZNsDvCN",
+        decimals: 6,
+    },
+];
+
+pub(crate) fn find_token_by_symbol(symbol: &str) -> Option<Token> {
+    let symbol = symbol.trim().to_uppercase();
+    TOKENS.iter().find(|token| token.symbol == symbol).copied()
+}
+
+pub(crate) fn find_token_by_mint(mint: &str) -> Option<Token> {
+    TOKENS.iter().find(|token| token.mint == mint).copied()
+}
+
+impl Token {
+    /// Raw amount in the smallest unit, `None` when it doesn't fit.
+    pub(crate) fn to_raw(self, amount: f64) -> Option<u64> {
+        let raw = (amount * 10f64.powi(self.decimals as i32)).round();
+        match raw.is_finite() && raw >= 1.0 && raw < u64::MAX as f64 {
+            true => Some(raw as u64),
+            false => None,
+        }
+    }
+
+    pub(crate) fn to_ui(self, raw: u64) -> f64 {
+        raw as f64 / 10f64.powi(self.decimals as i32)
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_token_amounts() {
+    let sol = find_token_by_symbol("sol").unwrap();
+
+    assert_eq!(sol.to_raw(1.5), Some(1_500_000_000));
+    assert_eq!(sol.to_raw(0.0), None);
+    assert_eq!(sol.to_ui(250_000_000), 0.25);
+    assert_eq!(find_token_by_mint(sol.mint), Some(sol));
+    assert_eq!(find_token_by_symbol("NOPE"), None);
+}
@@ -0,0 +1,321 @@
+//! Key-value storage the bot keeps products, prices and user data in.
+
+use async_trait::async_trait;
+use chrono::Utc;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::Error;
+
+pub(crate) mod bindings;
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) mod file;
+pub(crate) mod json;
+pub(crate) mod keys;
+pub(crate) mod memory;
+
+/// Most keys a single `list` call returns, as on Workers KV.
+pub(crate) const MAX_LIST_LIMIT: usize = 1000;
+
+/// How long a value is kept and what is stored next to it, serialized as Workers KV expects.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PutOptions {
+    /// Seconds from now.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) expiration_ttl: Option<u64>,
+    /// Seconds since the epoch, wins over `expiration_ttl`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) expiration: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) metadata: Option<Value>,
+}
+
+impl PutOptions {
+    pub(crate) fn ttl(seconds: u64) -> Self {
+        PutOptions {
+            expiration_ttl: Some(seconds),
+            ..Default::default()
+        }
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn expire_at(timestamp: i64) -> Self {
+        PutOptions {
+            expiration: Some(timestamp),
+            ..Default::default()
+        }
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn with_metadata<M: Serialize>(self, metadata: &M) -> Result<Self, Error> {
+        Ok(PutOptions {
+            metadata: Some(serde_json::to_value(metadata)?),
+            ..self
+        })
+    }
+
+    /// Absolute expiration in seconds since the epoch, if any.
+    pub(crate) fn expires_at(&self, now: i64) -> Option<i64> {
+        self.expiration
+            .or_else(|| self.expiration_ttl.map(|ttl| now + ttl as i64))
+    }
+}
+
+/// Page of keys to `list`, `cursor` comes from the previous page.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub(crate) struct ListOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) prefix: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) limit: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) cursor: Option<String>,
+}
+
+impl ListOptions {
+    pub(crate) fn prefix(prefix: &str) -> Self {
+        ListOptions {
+            prefix: Some(prefix.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn limit(&self) -> usize {
+        self.limit
+            .unwrap_or(MAX_LIST_LIMIT)
+            .clamp(1, MAX_LIST_LIMIT)
+    }
+}
+
+/// Key returned by `list`, `expiration` in seconds since the epoch.
+#[allow(dead_code)]
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub(crate) struct ListKey {
+    pub(crate) name: String,
+    pub(crate) expiration: Option<i64>,
+    pub(crate) metadata: Option<Value>,
+}
+
+impl ListKey {
+    #[allow(dead_code)]
+    pub(crate) fn metadata_as<M: DeserializeOwned>(&self) -> Option<M> {
+        serde_json::from_value(self.metadata.clone()?).ok()
+    }
+}
+
+/// One page of keys, more pages follow from `cursor` until `list_complete`.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+pub(crate) struct ListResult {
+    pub(crate) keys: Vec<ListKey>,
+    pub(crate) list_complete: bool,
+    pub(crate) cursor: Option<String>,
+}
+
+#[allow(dead_code)]
+#[async_trait(?Send)]
+pub(crate) trait KeyValueStore {
+    async fn get_with_metadata(&self, key: &str)
+        -> Result<Option<(Vec<u8>, Option<Value>)>, Error>;
+
+    async fn put(&self, key: &str, value: &[u8], options: PutOptions) -> Result<(), Error>;
+
+    async fn delete(&self, key: &str) -> Result<(), Error>;
+
+    /// One page of keys sorted by name.
+    async fn list(&self, options: ListOptions) -> Result<ListResult, Error>;
+
+    /// Every key starting with `prefix`, following the cursor page by page.
+    async fn list_all(&self, prefix: &str) -> Result<Vec<ListKey>, Error> {
+        let mut options = ListOptions::prefix(prefix);
+        let mut keys = Vec::new();
+        loop {
+            let page = self.list(options.clone()).await?;
+            keys.extend(page.keys);
+            match page.cursor {
+                Some(cursor) if !page.list_complete => options.cursor = Some(cursor),
+                _ => return Ok(keys),
+            }
+        }
+    }
+
+    async fn get_vec(&self, key: &str) -> Result<Option<Vec<u8>>, Error> {
+        Ok(self.get_with_metadata(key).await?.map(|(value, _)| value))
+    }
+
+    async fn get_text(&self, key: &str) -> Result<Option<String>, Error> {
+        match self.get_vec(key).await? {
+            Some(value) => String::from_utf8(value)
+                .map(Some)
+                .map_err(|_| Error::InvalidPayload(format!("{} is not text", key))),
+            None => Ok(None),
+        }
+    }
+
+    async fn put_vec(&self, key: &str, value: &[u8], ttl: u64) -> Result<(), Error> {
+        self.put(key, value, PutOptions::ttl(ttl)).await
+    }
+
+    async fn put_text(&self, key: &str, value: &str, ttl: u64) -> Result<(), Error> {
+        self.put(key, value.as_bytes(), PutOptions::ttl(ttl)).await
+    }
+}
+
+/// Value and metadata of `key`, metadata that doesn't fit `M` reads as `None`.
+#[allow(dead_code)]
+pub(crate) async fn get_with_metadata_as<M: DeserializeOwned>(
+    kv: &dyn KeyValueStore,
+    key: &str,
+) -> Result<Option<(Vec<u8>, Option<M>)>, Error> {
+    Ok(kv.get_with_metadata(key).await?.map(|(value, metadata)| {
+        (
+            value,
+            metadata.and_then(|metadata| serde_json::from_value(metadata).ok()),
+        )
+    }))
+}
+
+/// Value kept by the native stores.
+#[allow(dead_code)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub(crate) struct StoredValue {
+    /// Base64 so binary values like chart PNGs survive JSON.
+    pub(crate) value: String,
+    pub(crate) expiration: Option<i64>,
+    pub(crate) metadata: Option<Value>,
+}
+
+#[allow(dead_code)]
+impl StoredValue {
+    pub(crate) fn new(value: &[u8], options: PutOptions) -> Self {
+        StoredValue {
+            value: base64::encode(value),
+            expiration: options.expires_at(Utc::now().timestamp()),
+            metadata: options.metadata,
+        }
+    }
+
+    pub(crate) fn is_expired(&self, now: i64) -> bool {
+        matches!(self.expiration, Some(expiration) if expiration <= now)
+    }
+
+    pub(crate) fn value(&self) -> Vec<u8> {
+        base64::decode(&self.value).unwrap_or_default()
+    }
+
+    pub(crate) fn list_key(&self, name: &str) -> ListKey {
+        ListKey {
+            name: name.to_string(),
+            expiration: self.expiration,
+            metadata: self.metadata.clone(),
+        }
+    }
+}
+
+/// Page of live `values` after the cursor, the cursor being the last name of the previous page.
+#[allow(dead_code)]
+pub(crate) fn list_page<'a>(
+    values: impl Iterator<Item = (&'a String, &'a StoredValue)>,
+    options: &ListOptions,
+    now: i64,
+) -> ListResult {
+    let prefix = options.prefix.as_deref().unwrap_or_default();
+    let limit = options.limit();
+    let mut keys: Vec<ListKey> = values
+        .filter(|(name, stored)| name.starts_with(prefix) && !stored.is_expired(now))
+        .filter(|(name, _)| match &options.cursor {
+            Some(cursor) => name.as_str() > cursor.as_str(),
+            None => true,
+        })
+        .take(limit + 1)
+        .map(|(name, stored)| stored.list_key(name))
+        .collect();
+
+    let list_complete = keys.len() <= limit;
+    keys.truncate(limit);
+    let cursor = match list_complete {
+        true => None,
+        false => keys.last().map(|key| key.name.clone()),
+    };
+
+    ListResult {
+        keys,
+        list_complete,
+        cursor,
+    }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_list_pages_and_typed_metadata() {
+    use crate::kv::memory::MemoryKv;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Meta {
+        version: u32,
+    }
+
+    let kv = MemoryKv::default();
+    for day in 1..=5 {
+        let options = PutOptions::ttl(60)
+            .with_metadata(&Meta { version: day })
+            .unwrap();
+        kv.put(&format!("SOL:2022-01-0{}", day), b"{}", options)
+            .await
+            .unwrap();
+    }
+    kv.put_text("BTC:2022-01-01", "{}", 60).await.unwrap();
+
+    let first = kv
+        .list(ListOptions {
+            limit: Some(2),
+            ..ListOptions::prefix("SOL:")
+        })
+        .await
+        .unwrap();
+    assert_eq!(first.keys.len(), 2);
+    assert!(!first.list_complete);
+    assert_eq!(first.cursor.as_deref(), Some("SOL:2022-01-02"));
+    assert_eq!(
+        first.keys[1].metadata_as::<Meta>(),
+        Some(Meta { version: 2 })
+    );
+
+    let rest = kv
+        .list(ListOptions {
+            cursor: first.cursor,
+            ..ListOptions::prefix("SOL:")
+        })
+        .await
+        .unwrap();
+    assert_eq!(rest.keys.len(), 3);
+    assert!(rest.list_complete);
+    assert_eq!(kv.list_all("SOL:").await.unwrap().len(), 5);
+
+    let (_, meta) = get_with_metadata_as::<Meta>(&kv, "SOL:2022-01-05")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(meta, Some(Meta { version: 5 }));
+}
+
+#[cfg(test)]
+#[test]
+fn test_put_options_serialize() {
+    let now = 1_640_995_200;
+
+    assert_eq!(
+        serde_json::to_value(PutOptions::ttl(60)).unwrap(),
+        serde_json::json!({ "expirationTtl": 60 })
+    );
+    assert_eq!(PutOptions::ttl(60).expires_at(now), Some(now + 60));
+    assert_eq!(
+        PutOptions {
+            expiration_ttl: Some(60),
+            ..PutOptions::expire_at(now + 3600)
+        }
+        .expires_at(now),
+        Some(now + 3600)
+    );
+    assert_eq!(PutOptions::default().expires_at(now), None);
+}
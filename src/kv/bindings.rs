@@ -0,0 +1,110 @@
+use crate::{kv::KeyValueStore, solana::web3::Cluster};
+
+/// What a namespace holds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum KvRole {
+    /// Synced symbols and their price accounts, configured accounts like lending pools.
+    Products,
+    /// Daily price history.
+    Prices,
+    /// Alerts and watchlists, shared by every cluster.
+    Users,
+}
+
+/// Every KV namespace bound to the worker.
+pub(crate) struct Bindings {
+    pub(crate) devnet_products: Box<dyn KeyValueStore>,
+    pub(crate) devnet_prices: Box<dyn KeyValueStore>,
+    pub(crate) mainnet_products: Box<dyn KeyValueStore>,
+    pub(crate) mainnet_prices: Box<dyn KeyValueStore>,
+    pub(crate) users: Box<dyn KeyValueStore>,
+}
+
+impl Bindings {
+    /// Store of `role` on `cluster`, clusters without namespaces of their own use devnet.
+    pub(crate) fn store(&self, role: KvRole, cluster: &Cluster) -> &dyn KeyValueStore {
+        let store = match (role, cluster) {
+            (KvRole::Users, _) => &self.users,
+            (KvRole::Products, Cluster::MainnetBeta) => &self.mainnet_products,
+            (KvRole::Products, _) => &self.devnet_products,
+            (KvRole::Prices, Cluster::MainnetBeta) => &self.mainnet_prices,
+            (KvRole::Prices, _) => &self.devnet_prices,
+        };
+
+        store.as_ref()
+    }
+
+    pub(crate) fn products(&self, cluster: &Cluster) -> &dyn KeyValueStore {
+        self.store(KvRole::Products, cluster)
+    }
+
+    pub(crate) fn prices(&self, cluster: &Cluster) -> &dyn KeyValueStore {
+        self.store(KvRole::Prices, cluster)
+    }
+
+    pub(crate) fn users(&self) -> &dyn KeyValueStore {
+        self.store(KvRole::Users, &Cluster::Devnet)
+    }
+
+    /// Separate in-memory namespaces, for tests and the native tools.
+    #[allow(dead_code)]
+    pub(crate) fn in_memory() -> Self {
+        use crate::kv::memory::MemoryKv;
+
+        Bindings {
+            devnet_products: Box::new(MemoryKv::default()),
+            devnet_prices: Box::new(MemoryKv::default()),
+            mainnet_products: Box::new(MemoryKv::default()),
+            mainnet_prices: Box::new(MemoryKv::default()),
+            users: Box::new(MemoryKv::default()),
+        }
+    }
+
+    /// One JSON file per namespace in `dir`, named as bound in `wrangler.toml`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn in_directory(dir: &std::path::Path) -> Self {
+        use crate::kv::file::FileKv;
+
+        let store = |name: &str| -> Box<dyn KeyValueStore> {
+            Box::new(FileKv::new(dir.join(format!("{}.json", name))))
+        };
+
+        Bindings {
+            devnet_products: store("DEVNET_PYTH_PRODUCTS"),
+            devnet_prices: store("DEVNET_PYTH_PRICES"),
+            mainnet_products: store("MAINNETBETA_PYTH_PRODUCTS"),
+            mainnet_prices: store("MAINNETBETA_PYTH_PRICES"),
+            users: store("USERS"),
+        }
+    }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_store_by_role_and_cluster() {
+    let bindings = Bindings::in_memory();
+
+    bindings
+        .products(&Cluster::MainnetBeta)
+        .put_text("SYMBOLS", "Crypto.SOL/USD", 60)
+        .await
+        .unwrap();
+
+    for (role, cluster, expected) in [
+        (
+            KvRole::Products,
+            Cluster::MainnetBeta,
+            Some("Crypto.SOL/USD"),
+        ),
+        (KvRole::Products, Cluster::Devnet, None),
+        (KvRole::Prices, Cluster::MainnetBeta, None),
+        (KvRole::Users, Cluster::MainnetBeta, None),
+    ] {
+        let symbols = bindings
+            .store(role, &cluster)
+            .get_text("SYMBOLS")
+            .await
+            .unwrap();
+        assert_eq!(symbols.as_deref(), expected);
+    }
+}
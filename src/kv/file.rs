@@ -0,0 +1,131 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use async_trait::async_trait;
+use chrono::Utc;
+use serde_json::Value;
+
+use crate::{
+    error::Error,
+    kv::{list_page, KeyValueStore, ListOptions, ListResult, PutOptions, StoredValue},
+};
+
+#[allow(dead_code)]
+type Values = BTreeMap<String, StoredValue>;
+
+/// Store kept in one JSON file, so the native tools keep their data between runs.
+#[allow(dead_code)]
+pub(crate) struct FileKv {
+    path: PathBuf,
+    // Serializes read-modify-write of the file within the process.
+    lock: Mutex<()>,
+}
+
+#[allow(dead_code)]
+impl FileKv {
+    pub(crate) fn new(path: impl AsRef<Path>) -> Self {
+        FileKv {
+            path: path.as_ref().to_path_buf(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    fn read(&self) -> Result<Values, Error> {
+        match fs::read_to_string(&self.path) {
+            Ok(json) => Ok(serde_json::from_str(&json)?),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(Values::new()),
+            Err(error) => Err(Error::KvFailed(error.to_string())),
+        }
+    }
+
+    fn write(&self, values: &Values) -> Result<(), Error> {
+        let now = Utc::now().timestamp();
+        let values: Values = values
+            .iter()
+            .filter(|(_, stored)| !stored.is_expired(now))
+            .map(|(name, stored)| (name.clone(), stored.clone()))
+            .collect();
+
+        fs::write(&self.path, serde_json::to_string_pretty(&values)?)
+            .map_err(|error| Error::KvFailed(error.to_string()))
+    }
+}
+
+#[async_trait(?Send)]
+impl KeyValueStore for FileKv {
+    async fn get_with_metadata(
+        &self,
+        key: &str,
+    ) -> Result<Option<(Vec<u8>, Option<Value>)>, Error> {
+        let _lock = self.lock.lock().unwrap();
+        let now = Utc::now().timestamp();
+
+        Ok(self
+            .read()?
+            .get(key)
+            .filter(|stored| !stored.is_expired(now))
+            .map(|stored| (stored.value(), stored.metadata.clone())))
+    }
+
+    async fn put(&self, key: &str, value: &[u8], options: PutOptions) -> Result<(), Error> {
+        let _lock = self.lock.lock().unwrap();
+        let mut values = self.read()?;
+        values.insert(key.to_string(), StoredValue::new(value, options));
+
+        self.write(&values)
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), Error> {
+        let _lock = self.lock.lock().unwrap();
+        let mut values = self.read()?;
+        values.remove(key);
+
+        self.write(&values)
+    }
+
+    async fn list(&self, options: ListOptions) -> Result<ListResult, Error> {
+        let _lock = self.lock.lock().unwrap();
+
+        Ok(list_page(
+            self.read()?.iter(),
+            &options,
+            Utc::now().timestamp(),
+        ))
+    }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_file_kv_persists() {
+    let path = std::env::temp_dir().join(format!("catboard-kv-{}.json", std::process::id()));
+
+    let kv = FileKv::new(&path);
+    kv.put_text("SYMBOLS", "SOL,BTC", 60).await.unwrap();
+    kv.put_vec("SOL.png", &[137, 80, 78, 71], 60).await.unwrap();
+
+    let reopened = FileKv::new(&path);
+    assert_eq!(
+        reopened.get_text("SYMBOLS").await.unwrap().as_deref(),
+        Some("SOL,BTC")
+    );
+    assert_eq!(
+        reopened.get_vec("SOL.png").await.unwrap(),
+        Some(vec![137, 80, 78, 71])
+    );
+
+    reopened.delete("SYMBOLS").await.unwrap();
+    let names: Vec<String> = kv
+        .list_all("")
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|key| key.name)
+        .collect();
+    assert_eq!(names, vec!["SOL.png"]);
+
+    fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,142 @@
+//! JSON values stored with their schema version, as `{"version":1,"data":...}`.
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    error::Error,
+    kv::{KeyValueStore, PutOptions},
+};
+
+pub(crate) trait Versioned: Serialize + DeserializeOwned {
+    /// Bump when the stored shape changes, and teach `migrate` the old one.
+    const VERSION: u32;
+
+    /// Upgrade `data` stored by an older `version`, values stored before versioning read as `0`.
+    /// Returning `None` fails the read, so the record isn't overwritten by a caller that couldn't
+    /// read it. Older records keep the same shape by default.
+    fn migrate(version: u32, data: Value) -> Option<Value> {
+        let _ = version;
+        Some(data)
+    }
+}
+
+impl<T: Versioned> Versioned for Vec<T> {
+    const VERSION: u32 = T::VERSION;
+
+    /// Every item or none, a single item that can't be migrated fails the whole list.
+    fn migrate(version: u32, data: Value) -> Option<Value> {
+        match data {
+            Value::Array(items) => items
+                .into_iter()
+                .map(|item| T::migrate(version, item))
+                .collect::<Option<Vec<_>>>()
+                .map(Value::Array),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Envelope<T> {
+    version: u32,
+    data: T,
+}
+
+pub(crate) fn encode<T: Versioned>(value: &T) -> Result<String, Error> {
+    Ok(serde_json::to_string(&Envelope {
+        version: T::VERSION,
+        data: value,
+    })?)
+}
+
+/// Read a stored value, migrating it first when an older version wrote it.
+pub(crate) fn decode<T: Versioned>(text: &str) -> Result<Option<T>, Error> {
+    let value: Value = serde_json::from_str(text)?;
+    let (version, data) = match serde_json::from_value::<Envelope<Value>>(value.clone()) {
+        Ok(envelope) => (envelope.version, envelope.data),
+        Err(_) => (0, value),
+    };
+
+    if version > T::VERSION {
+        return Err(Error::InvalidPayload(format!(
+            "schema version {} is newer than {}",
+            version,
+            T::VERSION
+        )));
+    }
+    let data = match version < T::VERSION {
+        true => T::migrate(version, data).ok_or_else(|| {
+            Error::InvalidPayload(format!(
+                "schema version {} can't be migrated to {}",
+                version,
+                T::VERSION
+            ))
+        })?,
+        false => data,
+    };
+
+    Ok(Some(serde_json::from_value(data)?))
+}
+
+pub(crate) async fn get_json<T: Versioned>(
+    kv: &dyn KeyValueStore,
+    key: &str,
+) -> Result<Option<T>, Error> {
+    match kv.get_text(key).await? {
+        Some(text) => decode(&text),
+        None => Ok(None),
+    }
+}
+
+pub(crate) async fn put_json<T: Versioned>(
+    kv: &dyn KeyValueStore,
+    key: &str,
+    value: &T,
+    options: PutOptions,
+) -> Result<(), Error> {
+    kv.put(key, encode(value)?.as_bytes(), options).await
+}
+
+#[cfg(test)]
+#[test]
+fn test_decode_migrates_old_versions() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Price {
+        usd: f64,
+    }
+
+    impl Versioned for Price {
+        const VERSION: u32 = 2;
+
+        // v0 and v1 kept a bare number
+        fn migrate(version: u32, data: Value) -> Option<Value> {
+            match version {
+                0 | 1 => Some(serde_json::json!({ "usd": data.as_f64()? })),
+                _ => Some(data),
+            }
+        }
+    }
+
+    let price = Price { usd: 1.5 };
+    assert_eq!(
+        encode(&price).unwrap(),
+        r#"{"version":2,"data":{"usd":1.5}}"#
+    );
+    assert_eq!(
+        decode::<Price>(&encode(&price).unwrap()).unwrap(),
+        Some(price)
+    );
+    assert_eq!(
+        decode::<Price>(r#"{"version":1,"data":2.5}"#).unwrap(),
+        Some(Price { usd: 2.5 })
+    );
+    assert_eq!(decode::<Price>("3.5").unwrap(), Some(Price { usd: 3.5 }));
+    assert_eq!(
+        decode::<Vec<Price>>("[1.0,2.0]").unwrap(),
+        Some(vec![Price { usd: 1.0 }, Price { usd: 2.0 }])
+    );
+    assert!(decode::<Vec<Price>>(r#"[1.0,"oops",2.0]"#).is_err());
+    assert!(decode::<Price>(r#"{"version":1,"data":"oops"}"#).is_err());
+    assert!(decode::<Price>(r#"{"version":3,"data":{"usd":1.5}}"#).is_err());
+}
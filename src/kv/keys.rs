@@ -0,0 +1,93 @@
+//! Every key the bot stores, so their formats live in one place.
+
+use chrono::NaiveDate;
+
+/// Pairs to sync, e.g. `Crypto.BTC/USD,Crypto.SOL/USD`.
+pub(crate) const SYMBOLS: &str = "SYMBOLS";
+/// RFC 3339 time of the last product or price sync.
+pub(crate) const LAST_SYNCED_AT: &str = "LAST_SYNCED_AT";
+
+/// `Crypto.SOL/USD:price_account`, written by the product sync.
+pub(crate) fn price_account(pair: &str) -> String {
+    format!("{}:price_account", pair)
+}
+
+/// `Crypto.SOL/USD:2022-01-01`, one day of prices.
+pub(crate) fn daily_price(pair: &str, date: NaiveDate) -> String {
+    format!("{}:{}", pair, date.format("%Y-%m-%d"))
+}
+
+/// `Crypto.SOL/USD:cached_price`, the last price fetched from Pyth.
+pub(crate) fn cached_price(pair: &str) -> String {
+    format!("{}:cached_price", pair)
+}
+
+/// `Crypto.SOL/USD:indicators`, the indicator line of `/price` kept by the price sync.
+pub(crate) fn indicator_summary(pair: &str) -> String {
+    format!("{}:indicators", pair)
+}
+
+/// `Crypto.SOL/USD:indicator_state`, what the price sync advances the indicator line from.
+pub(crate) fn indicator_state(pair: &str) -> String {
+    format!("{}:indicator_state", pair)
+}
+
+/// `alert:Crypto.SOL/USD:1234:SOL-1`, one alert of a user.
+pub(crate) fn alert(pair: &str, user_id: &str, id: &str) -> String {
+    format!("{}{}", user_alerts_prefix(pair, user_id), id)
+}
+
+/// `alert:Crypto.SOL/USD:`, listing every alert on a pair.
+pub(crate) fn alerts_prefix(pair: &str) -> String {
+    format!("alert:{}:", pair)
+}
+
+/// `alert:Crypto.SOL/USD:1234:`, listing the alerts of a user on a pair.
+pub(crate) fn user_alerts_prefix(pair: &str, user_id: &str) -> String {
+    format!("{}{}:", alerts_prefix(pair), user_id)
+}
+
+/// `user:1234:watchlist`.
+pub(crate) fn watchlist(user_id: &str) -> String {
+    format!("user:{}:watchlist", user_id)
+}
+
+/// `Francium.USDC:pool_account`, configured by hand per lending protocol and asset.
+pub(crate) fn pool_account(protocol: &str, asset: &str) -> String {
+    format!("{}.{}:pool_account", protocol, asset.to_uppercase())
+}
+
+#[cfg(test)]
+#[test]
+fn test_key_formats() {
+    assert_eq!(
+        price_account("Crypto.SOL/USD"),
+        "Crypto.SOL/USD:price_account"
+    );
+    assert_eq!(
+        daily_price("Marinade.mSOL/SOL", NaiveDate::from_ymd(2022, 1, 1)),
+        "Marinade.mSOL/SOL:2022-01-01"
+    );
+    assert_eq!(
+        cached_price("Crypto.SOL/USD"),
+        "Crypto.SOL/USD:cached_price"
+    );
+    assert_eq!(
+        indicator_summary("Crypto.SOL/USD"),
+        "Crypto.SOL/USD:indicators"
+    );
+    assert_eq!(
+        indicator_state("Crypto.SOL/USD"),
+        "Crypto.SOL/USD:indicator_state"
+    );
+    assert_eq!(
+        alert("Crypto.SOL/USD", "1234", "SOL-1"),
+        "alert:Crypto.SOL/USD:1234:SOL-1"
+    );
+    assert!(alert("Crypto.SOL/USD", "1234", "SOL-1").starts_with(&alerts_prefix("Crypto.SOL/USD")));
+    assert_eq!(watchlist("1234"), "user:1234:watchlist");
+    assert_eq!(
+        pool_account("Francium", "usdc"),
+        "Francium.USDC:pool_account"
+    );
+}
@@ -0,0 +1,110 @@
+use std::{collections::BTreeMap, sync::Mutex};
+
+use async_trait::async_trait;
+use chrono::Utc;
+use serde_json::Value;
+
+use crate::{
+    error::Error,
+    kv::{list_page, KeyValueStore, ListOptions, ListResult, PutOptions, StoredValue},
+};
+
+/// Store living as long as the process, for tests and the native tools.
+#[allow(dead_code)]
+#[derive(Default)]
+pub(crate) struct MemoryKv {
+    values: Mutex<BTreeMap<String, StoredValue>>,
+}
+
+#[async_trait(?Send)]
+impl KeyValueStore for MemoryKv {
+    async fn get_with_metadata(
+        &self,
+        key: &str,
+    ) -> Result<Option<(Vec<u8>, Option<Value>)>, Error> {
+        let now = Utc::now().timestamp();
+        let values = self.values.lock().unwrap();
+
+        Ok(values
+            .get(key)
+            .filter(|stored| !stored.is_expired(now))
+            .map(|stored| (stored.value(), stored.metadata.clone())))
+    }
+
+    async fn put(&self, key: &str, value: &[u8], options: PutOptions) -> Result<(), Error> {
+        self.values
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), StoredValue::new(value, options));
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), Error> {
+        self.values.lock().unwrap().remove(key);
+        Ok(())
+    }
+
+    async fn list(&self, options: ListOptions) -> Result<ListResult, Error> {
+        let values = self.values.lock().unwrap();
+        let start = options
+            .prefix
+            .clone()
+            .max(options.cursor.clone())
+            .unwrap_or_default();
+
+        Ok(list_page(
+            values.range(start..),
+            &options,
+            Utc::now().timestamp(),
+        ))
+    }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_memory_kv() {
+    let kv = MemoryKv::default();
+
+    kv.put_text("SOL:price_account", "H6AR", 60).await.unwrap();
+    kv.put_vec("SOL.png", &[0, 159, 146, 150], 60)
+        .await
+        .unwrap();
+    kv.put(
+        "user:1:watchlist",
+        b"[]",
+        PutOptions {
+            metadata: Some(serde_json::json!({ "version": 1 })),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    kv.put("SOL:gone", b"x", PutOptions::ttl(0)).await.unwrap();
+
+    assert_eq!(
+        kv.get_text("SOL:price_account").await.unwrap().as_deref(),
+        Some("H6AR")
+    );
+    assert_eq!(
+        kv.get_vec("SOL.png").await.unwrap(),
+        Some(vec![0, 159, 146, 150])
+    );
+    assert!(kv.get_text("SOL.png").await.is_err());
+    assert_eq!(kv.get_text("SOL:gone").await.unwrap(), None);
+    assert_eq!(
+        kv.get_with_metadata("user:1:watchlist").await.unwrap(),
+        Some((b"[]".to_vec(), Some(serde_json::json!({ "version": 1 }))))
+    );
+
+    let names: Vec<String> = kv
+        .list_all("SOL")
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|key| key.name)
+        .collect();
+    assert_eq!(names, vec!["SOL.png", "SOL:price_account"]);
+
+    kv.delete("SOL.png").await.unwrap();
+    assert_eq!(kv.get_vec("SOL.png").await.unwrap(), None);
+}
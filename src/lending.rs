@@ -0,0 +1,130 @@
+pub(crate) mod francium;
+
+use std::str::FromStr;
+
+use crate::{
+    endpoints::Endpoints,
+    error::Error,
+    kv::{bindings::Bindings, keys},
+    solana::{
+        pubkey::Pubkey,
+        web3::{get_multiple_account_data, Cluster},
+    },
+};
+
+/// Rates of one lending pool, amounts in the asset's own units.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct LendingPool {
+    pub(crate) available: f64,
+    pub(crate) borrowed: f64,
+    /// Yearly rates in percent, before compounding.
+    pub(crate) borrow_apr: f64,
+    pub(crate) supply_apr: f64,
+}
+
+/// Yield in percent of `apr` compounded daily.
+fn compound_daily(apr: f64) -> f64 {
+    ((1.0 + apr / 100.0 / 365.0).powi(365) - 1.0) * 100.0
+}
+
+impl LendingPool {
+    /// Borrowed share of the pool in percent.
+    pub(crate) fn utilization(&self) -> f64 {
+        match self.available + self.borrowed {
+            total if total > 0.0 => self.borrowed / total * 100.0,
+            _ => 0.0,
+        }
+    }
+
+    pub(crate) fn borrow_apy(&self) -> f64 {
+        compound_daily(self.borrow_apr)
+    }
+
+    pub(crate) fn supply_apy(&self) -> f64 {
+        compound_daily(self.supply_apr)
+    }
+}
+
+/// A protocol whose pools can be decoded from their on-chain account.
+pub(crate) trait LendingProtocol {
+    /// Name used in replies and KV keys, e.g. `Francium`.
+    fn name(&self) -> &'static str;
+
+    /// App to lend in.
+    fn url(&self) -> &'static str;
+
+    fn decode_pool(&self, data: &[u8]) -> Result<LendingPool, Error>;
+
+    /// Pool account of an uppercase `asset` known without configuration.
+    fn known_pool_account(&self, _asset: &str) -> Option<&'static str> {
+        None
+    }
+
+    /// Pool accounts configured per asset at `Francium.USDC:pool_account`, over the known ones.
+    fn pool_account_key(&self, asset: &str) -> String {
+        keys::pool_account(self.name(), asset)
+    }
+}
+
+pub(crate) fn lending_protocols() -> Vec<Box<dyn LendingProtocol>> {
+    vec![Box::new(francium::Francium)]
+}
+
+/// Pool of `asset` on `protocol`, `None` when it has no known or configured pool account.
+pub(crate) async fn fetch_lending_pool(
+    bindings: &Bindings,
+    endpoints: &Endpoints,
+    cluster: &Cluster,
+    protocol: &dyn LendingProtocol,
+    asset: &str,
+) -> Result<Option<LendingPool>, Error> {
+    let key = protocol.pool_account_key(asset);
+    let pool_account = bindings
+        .products(cluster)
+        .get_text(&key)
+        .await
+        .unwrap_or_default();
+    let pool_account = pool_account
+        .as_deref()
+        .or_else(|| protocol.known_pool_account(&asset.to_uppercase()));
+    let pubkey = match pool_account.map(Pubkey::from_str) {
+        Some(Ok(pubkey)) => pubkey,
+        _ => return Ok(None),
+    };
+
+    let data = get_multiple_account_data(&endpoints.rpc(cluster), &[pubkey])
+        .await?
+        .pop()
+        .flatten()
+        .ok_or_else(|| {
+            Error::NotFound(format!(
+                "{} pool of {}",
+                protocol.name(),
+                asset.to_uppercase()
+            ))
+        })?;
+    protocol.decode_pool(&data).map(Some)
+}
+
+#[cfg(test)]
+#[test]
+fn test_lending_pool_rates() {
+    let pool = LendingPool {
+        available: 30.0,
+        borrowed: 70.0,
+        borrow_apr: 10.0,
+        supply_apr: 6.3,
+    };
+
+    assert_eq!(pool.utilization(), 70.0);
+    assert!((pool.borrow_apy() - 10.5156).abs() < 1e-3);
+    assert_eq!(
+        francium::Francium.pool_account_key("usdc"),
+        "Francium.USDC:pool_account"
+    );
+    assert_eq!(
+        francium::Francium.known_pool_account("USDC"),
+        Some(francium::FRANCIUM_POOL_ACCOUNTS[0].1)
+    );
+    assert_eq!(francium::Francium.known_pool_account("BONK"), None);
+}
@@ -0,0 +1,153 @@
+use crate::{
+    error::Error,
+    lending::{LendingPool, LendingProtocol},
+    solana::reader::AccountReader,
+};
+
+const WAD: f64 = 1_000_000_000_000_000_000.0;
+
+/// Lending pool accounts on mainnet-beta by asset, `Francium.USDC:pool_account` overrides them.
+pub(crate) const FRANCIUM_POOL_ACCOUNTS: [(&str, &str); 1] =
+    [("USDC", "3JFwZkvgfoNvJdpjhJ7uYMnVzwaTRnyezYeZ1JSq9iLG")];
+
+/// `interestRateConfig` of a pool, a piecewise linear borrow rate with rates in percent and
+/// thresholds in percent of utilization.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct InterestModel {
+    threshold_1: u8,
+    threshold_2: u8,
+    base_1: u8,
+    factor_1: u16,
+    base_2: u8,
+    factor_2: u16,
+    base_3: u8,
+    factor_3: u16,
+}
+
+impl InterestModel {
+    fn borrow_apr(&self, utilization: f64) -> f64 {
+        let (t1, t2) = (self.threshold_1 as f64, self.threshold_2 as f64);
+        match utilization {
+            u if u <= t1 => self.base_1 as f64 + self.factor_1 as f64 * u / 100.0,
+            u if u <= t2 => self.base_2 as f64 + self.factor_2 as f64 * (u - t1) / 100.0,
+            u => self.base_3 as f64 + self.factor_3 as f64 * (u - t2) / 100.0,
+        }
+    }
+}
+
+/// Pools decoded with the `LendingPool` layout published in the Francium SDK,
+/// https://github.com/Francium-DeFi/francium-sdk, field names below are the layout's.
+pub(crate) struct Francium;
+
+impl LendingProtocol for Francium {
+    fn name(&self) -> &'static str {
+        "Francium"
+    }
+
+    fn url(&self) -> &'static str {
+        "https://francium.io/app/lend"
+    }
+
+    fn known_pool_account(&self, asset: &str) -> Option<&'static str> {
+        FRANCIUM_POOL_ACCOUNTS
+            .iter()
+            .find(|(known, _)| *known == asset)
+            .map(|(_, pool_account)| *pool_account)
+    }
+
+    fn decode_pool(&self, data: &[u8]) -> Result<LendingPool, Error> {
+        let mut reader = AccountReader::new(data, 0);
+
+        // version, lastUpdate { lastUpdatedSlot, stale }, lendingMarket
+        reader.skip(1 + 9 + 32)?;
+
+        // liquidity { mint_pubkey, mint_decimals, supply_pubkey, fee_receiver, oracle_pubkey,
+        // available_amount, borrowed_amount_wads, cumulative_borrow_rate_wads, market_price }
+        reader.skip(32)?;
+        let decimals = reader.u8()?;
+        reader.skip(32 * 3)?;
+        let available_amount = reader.u64()?;
+        let borrowed_amount_wads = reader.u128()?;
+        reader.skip(16 * 2)?;
+
+        // share { mint_pubkey, mint_total_supply, supply_pubkey }, credit, same fields
+        reader.skip((32 + 8 + 32) * 2)?;
+
+        // interestRateConfig
+
+        let model = InterestModel {
+            threshold_1: reader.u8()?,
+            threshold_2: reader.u8()?,
+            base_1: reader.u8()?,
+            factor_1: reader.u16()?,
+            base_2: reader.u8()?,
+            factor_2: reader.u16()?,
+            base_3: reader.u8()?,
+            factor_3: reader.u16()?,
+        };
+        let interest_reverse_rate = reader.u8()?;
+
+        let scale = 10f64.powi(decimals as i32);
+        let mut pool = LendingPool {
+            available: available_amount as f64 / scale,
+            borrowed: borrowed_amount_wads as f64 / WAD / scale,
+            borrow_apr: 0.0,
+            supply_apr: 0.0,
+        };
+        let utilization = pool.utilization();
+        pool.borrow_apr = model.borrow_apr(utilization);
+        // Lenders get the interest paid on the borrowed share, minus the reserve cut
+        pool.supply_apr =
+            pool.borrow_apr * utilization / 100.0 * (1.0 - interest_reverse_rate as f64 / 100.0);
+
+        Ok(pool)
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_decode_recorded_pool() {
+    use crate::testing::AccountFixture;
+
+    let fixture =
+        AccountFixture::from_json(include_str!("../../tests/fixtures/francium/usdc_pool.json"));
+    let data = fixture.data();
+    assert_eq!(fixture.pubkey, Francium.known_pool_account("USDC").unwrap());
+
+    let mint = AccountReader::new(&data, 1 + 9 + 32).pubkey().unwrap();
+    assert_eq!(
+        mint.to_string(),
+        "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"
+    );
+
+    let pool = Francium.decode_pool(&data).unwrap();
+
+    assert_eq!(pool.available, 12_000_000.0);
+    assert_eq!(pool.borrowed, 28_000_000.0);
+    assert_eq!(pool.utilization(), 70.0);
+    // 9% at 60% utilization, then 40% more up to 90%
+    assert!((pool.borrow_apr - 13.0).abs() < 1e-9);
+    // The interest of the borrowed 70%, less the 10% reserve cut
+    assert!((pool.supply_apr - 13.0 * 0.7 * 0.9).abs() < 1e-9);
+    assert!(Francium.decode_pool(&data[..100]).is_err());
+}
+
+#[cfg(test)]
+#[test]
+fn test_interest_model() {
+    let model = InterestModel {
+        threshold_1: 60,
+        threshold_2: 90,
+        base_1: 0,
+        factor_1: 15,
+        base_2: 9,
+        factor_2: 40,
+        base_3: 21,
+        factor_3: 300,
+    };
+
+    assert_eq!(model.borrow_apr(0.0), 0.0);
+    assert_eq!(model.borrow_apr(60.0), 9.0);
+    assert_eq!(model.borrow_apr(90.0), 21.0);
+    assert_eq!(model.borrow_apr(100.0), 51.0);
+}
@@ -1,23 +1,37 @@
 use cfg_if::cfg_if;
-use cloudflare::worker::WorkersKv;
 use context::Context;
+use std::rc::Rc;
 
 use http::HttpResponse;
 use wasm_bindgen::prelude::*;
 
-use js_sys::Promise;
+use js_sys::{Function, Promise};
 use wasm_bindgen_futures::future_to_promise;
 
-use crate::cloudflare::worker::WorkersKvJs;
+use crate::cloudflare::worker::BindingsJs;
+use crate::kv::bindings::Bindings;
+use crate::task::run_tasks;
 
 mod catboard;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod cli;
 mod cloudflare;
 mod context;
+#[cfg(not(target_arch = "wasm32"))]
+mod dev_server;
 mod discord;
+mod endpoints;
 mod error;
 mod http;
+mod jupiter;
+mod kv;
+mod lending;
+mod marinade;
 mod pyth;
 mod solana;
+mod task;
+#[cfg(test)]
+mod testing;
 
 cfg_if! {
     // When the `wee_alloc` feature is enabled, use `wee_alloc` as the global
@@ -30,22 +44,32 @@ cfg_if! {
 }
 
 #[wasm_bindgen]
-pub async fn wasm_main(context: JsValue, kv: WorkersKvJs) -> Promise {
+pub async fn wasm_main(context: JsValue, bindings: BindingsJs, wait_until: Function) -> Promise {
     future_to_promise(async move {
-        let value = JsValue::from_serde(
-            &(match context.into_serde::<Context>() {
-                Ok(ctx) => {
-                    let kv = WorkersKv { kv };
-                    ctx.handle_http_request(&kv).await
-                }
-
-                Err(error) => HttpResponse {
-                    status: 400,
-                    body: error.to_string(),
-                },
-            }),
-        )
-        .unwrap();
+        let bindings: Rc<Bindings> = Rc::new(bindings.into());
+        let mut response = match context.into_serde::<Context>() {
+            Ok(ctx) => ctx.handle_http_request(&bindings).await,
+
+            Err(error) => HttpResponse {
+                status: 400,
+                body: error.to_string(),
+                ..Default::default()
+            },
+        };
+
+        // Keep the worker alive until follow-ups and other tasks are done
+        let tasks = std::mem::take(&mut response.tasks);
+        if !tasks.is_empty() {
+            let promise = future_to_promise(async move {
+                run_tasks(tasks, bindings)
+                    .await
+                    .map_err(|error| JsValue::from_str(&error.to_string()))?;
+                Ok(JsValue::UNDEFINED)
+            });
+            wait_until.call1(&JsValue::NULL, &promise)?;
+        }
+
+        let value = JsValue::from_serde(&response).unwrap();
 
         Ok(value.into())
     })
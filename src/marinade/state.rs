@@ -0,0 +1,197 @@
+use std::str::FromStr;
+
+use crate::{
+    error::Error,
+    solana::{
+        pubkey::Pubkey,
+        reader::AccountReader,
+        web3::{get_multiple_account_data, Cluster},
+    },
+};
+
+/// Marinade state account on mainnet-beta.
+pub(crate) const MARINADE_STATE_ACCOUNT: &str = "8szGkuLTAux9XMgZ2vtY39jVSowEcpBfFfD8hXSEqdGC";
+/// Marinade liquid staking program.
+pub(crate) const MARINADE_PROGRAM_ID: &str = "MarBmsSgKXdrN1egZf5sqe1TMai9K1rChYNDJgjq7aD";
+pub(crate) const MARINADE_STAKE_URL: &str = "https://marinade.finance/app/staking";
+/// mSOL price in SOL, recorded daily by the price sync at `Marinade.mSOL/SOL:2022-01-01`.
+pub(crate) const MSOL_PAIR: &str = "Marinade.mSOL/SOL";
+
+/// `msol_price` is a fixed point number with this denominator.
+const PRICE_DENOMINATOR: f64 = 4_294_967_296.0;
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+/// Anchor account discriminator.
+const DISCRIMINATOR_LEN: usize = 8;
+
+/// Fields of the Marinade `State` account we show, amounts in lamports.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct MarinadeState {
+    pub(crate) msol_mint: Pubkey,
+    pub(crate) reward_fee_bps: u32,
+    pub(crate) validator_count: u32,
+    pub(crate) last_stake_delta_epoch: u64,
+    pub(crate) delayed_unstake_cooling_down: u64,
+    pub(crate) total_active_balance: u64,
+    pub(crate) available_reserve_balance: u64,
+    pub(crate) msol_supply: u64,
+    pub(crate) msol_price: u64,
+    pub(crate) circulating_ticket_balance: u64,
+    pub(crate) emergency_cooling_down: u64,
+}
+
+/// `List { account, item_size, count, new_account, copied_count }`, returns `count`.
+fn read_list_count(reader: &mut AccountReader) -> Result<u32, Error> {
+    reader.skip(32 + 4)?;
+    let count = reader.u32()?;
+    reader.skip(32 + 4)?;
+    Ok(count)
+}
+
+impl MarinadeState {
+    pub(crate) fn decode(data: &[u8]) -> Result<MarinadeState, Error> {
+        let mut reader = AccountReader::new(data, DISCRIMINATOR_LEN);
+
+        let msol_mint = reader.pubkey()?;
+        // admin_authority, operational_sol_account, treasury_msol_account, 2 bump seeds, rent_exempt_for_token_acc
+        reader.skip(32 * 3 + 2 + 8)?;
+        let reward_fee_bps = reader.u32()?;
+
+        // stake_system
+        read_list_count(&mut reader)?;
+        let delayed_unstake_cooling_down = reader.u64()?;
+        reader.skip(2 + 8)?;
+        let last_stake_delta_epoch = reader.u64()?;
+        reader.skip(8 + 4)?;
+
+        // validator_system
+        let validator_count = read_list_count(&mut reader)?;
+        reader.skip(32 + 4)?;
+        let total_active_balance = reader.u64()?;
+        reader.skip(1)?;
+
+        // liq_pool
+        reader.skip(32 + 3 + 32 + 8 + 4 * 3 + 8 * 3)?;
+
+        let available_reserve_balance = reader.u64()?;
+        let msol_supply = reader.u64()?;
+        let msol_price = reader.u64()?;
+        reader.skip(8)?;
+        let circulating_ticket_balance = reader.u64()?;
+        // lent_from_reserve, min_deposit, min_withdraw, staking_sol_cap
+        reader.skip(8 * 4)?;
+        let emergency_cooling_down = reader.u64()?;
+
+        Ok(MarinadeState {
+            msol_mint,
+            reward_fee_bps,
+            validator_count,
+            last_stake_delta_epoch,
+            delayed_unstake_cooling_down,
+            total_active_balance,
+            available_reserve_balance,
+            msol_supply,
+            msol_price,
+            circulating_ticket_balance,
+            emergency_cooling_down,
+        })
+    }
+
+    /// SOL per mSOL.
+    pub(crate) fn msol_price(&self) -> f64 {
+        self.msol_price as f64 / PRICE_DENOMINATOR
+    }
+
+    /// Total SOL under management, including stake still cooling down.
+    pub(crate) fn tvl_sol(&self) -> f64 {
+        let lamports = (self.total_active_balance as u128
+            + self.delayed_unstake_cooling_down as u128
+            + self.emergency_cooling_down as u128
+            + self.available_reserve_balance as u128)
+            .saturating_sub(self.circulating_ticket_balance as u128);
+
+        lamports as f64 / LAMPORTS_PER_SOL
+    }
+
+    pub(crate) fn msol_supply(&self) -> f64 {
+        self.msol_supply as f64 / LAMPORTS_PER_SOL
+    }
+}
+
+/// Yearly yield from the mSOL price growing from `old` to `new` over `days`.
+pub(crate) fn estimate_apy(old: f64, new: f64, days: i64) -> Option<f64> {
+    if days <= 0 || old <= 0.0 {
+        return None;
+    }
+
+    Some(((new / old).powf(365.0 / days as f64) - 1.0) * 100.0)
+}
+
+/// SOL reserve of the `state` account, a program derived address.
+#[allow(dead_code)]
+pub(crate) fn reserve_address(state: &Pubkey) -> Option<Pubkey> {
+    let program_id = Pubkey::from_str(MARINADE_PROGRAM_ID).unwrap();
+
+    Pubkey::find_program_address(&[state.as_ref(), b"reserve"], &program_id)
+        .map(|(address, _)| address)
+}
+
+pub(crate) async fn fetch_marinade_state(cluster: &Cluster) -> Result<MarinadeState, Error> {
+    let pubkey = Pubkey::from_str(MARINADE_STATE_ACCOUNT).unwrap();
+    let data = get_multiple_account_data(cluster, &[pubkey])
+        .await?
+        .pop()
+        .flatten()
+        .ok_or_else(|| Error::NotFound(format!("account {}", MARINADE_STATE_ACCOUNT)))?;
+
+    MarinadeState::decode(&data)
+}
+
+#[cfg(test)]
+#[test]
+fn test_decode_recorded_state() {
+    use crate::testing::AccountFixture;
+
+    let fixture =
+        AccountFixture::from_json(include_str!("../../tests/fixtures/marinade/state.json"));
+    assert_eq!(fixture.pubkey, MARINADE_STATE_ACCOUNT);
+    assert_eq!(fixture.owner, MARINADE_PROGRAM_ID);
+
+    let state = MarinadeState::decode(&fixture.data()).unwrap();
+
+    assert_eq!(
+        state.msol_mint.to_string(),
+        "mSoLzYCxHdYgdzU16g5QSh3i5K3z3KZK7ytfqcJm7So"
+    );
+    assert_eq!(state.reward_fee_bps, 600);
+    assert_eq!(state.validator_count, 428);
+    assert_eq!(state.last_stake_delta_epoch, 561);
+    assert_eq!(state.msol_supply, 5_450_000_000_000_000);
+    assert_eq!(state.msol_supply(), 5_450_000.0);
+    assert_eq!(state.msol_price, 5_068_061_409);
+    assert!((state.msol_price() - 1.18).abs() < 1e-9);
+    assert_eq!(state.tvl_sol(), 6_416_000.0);
+    // The mSOL price is the SOL under management per mSOL, as of the last update
+    let backing = state.msol_supply() * state.msol_price();
+    assert!((state.tvl_sol() / backing - 1.0).abs() < 0.01);
+    assert!(MarinadeState::decode(&fixture.data()[..300]).is_err());
+}
+
+#[cfg(test)]
+#[test]
+fn test_estimate_apy() {
+    let apy = estimate_apy(1.0, 1.0 + 0.07 / 365.0 * 30.0, 30).unwrap();
+
+    assert!((apy - 7.25).abs() < 0.05);
+    assert_eq!(estimate_apy(1.0, 1.1, 0), None);
+}
+
+#[cfg(test)]
+#[test]
+fn test_reserve_address() {
+    let state = Pubkey::from_str(MARINADE_STATE_ACCOUNT).unwrap();
+
+    assert_eq!(
+        reserve_address(&state).unwrap().to_string(),
+        "Du3Ysj1wKbxPKkuPPnvzQLQh8oMSVifs3jGZjJWXFmHN"
+    );
+}
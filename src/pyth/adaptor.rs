@@ -1,18 +1,19 @@
-use pyth_client::{load_mapping, load_price, load_product, PriceConf};
+use pyth_client::{load_mapping, load_price, load_product, PriceConf, Product};
 
+use crate::error::Error;
 use crate::solana::{
     pubkey::Pubkey,
-    web3::{get_account_data, Cluster},
+    web3::{get_account_data, get_multiple_account_data, Cluster},
 };
 use std::collections::HashMap;
 use std::str::FromStr;
 
 #[allow(dead_code)]
-fn get_pyth_mapping_account(target: &Cluster) -> &'static str {
+pub(crate) fn get_pyth_mapping_account(target: &Cluster) -> &'static str {
     match target {
         Cluster::MainnetBeta => "AHtgzX45WTKfkPG53L6WYhGEXwQkN1BVknET3sVsLL8J",
         Cluster::Testnet => "AFmdnt9ng1uVxqCmqwQJDAYC5cKTkw8gJKSM5PnzuF6z",
-        Cluster::Devnet => "BmA9Z6FjioHJPpjT39QazZyhDRUdZy2ezwx4GiDdE2u2",
+        Cluster::Devnet | Cluster::Custom(_) => "BmA9Z6FjioHJPpjT39QazZyhDRUdZy2ezwx4GiDdE2u2",
         _ => panic!("Unsupported cluster"),
     }
 }
@@ -31,50 +32,56 @@ where
     return val;
 }
 
+/// Symbol of a product, e.g. `Crypto.SOL/USD`, kept as its first attribute.
+pub(crate) fn get_product_symbol(product: &Product) -> String {
+    let mut pit = product.attr.iter();
+
+    let _ = get_attr_str(&mut pit);
+    get_attr_str(&mut pit)
+}
+
 #[allow(dead_code)]
 pub(crate) async fn fetch_pyth_product_account_by_symbol(
     cluster: &Cluster,
     symbol: &str,
-) -> Option<Pubkey> {
-    let product_accounts = fetch_pyth_product_accounts(cluster, Some(symbol)).await;
-    Some(*product_accounts.get(symbol).unwrap())
+) -> Result<Option<Pubkey>, Error> {
+    let product_accounts = fetch_pyth_product_accounts(cluster, Some(symbol)).await?;
+    Ok(product_accounts.get(symbol).copied())
 }
 
 #[allow(dead_code)]
 pub(crate) async fn fetch_pyth_product_accounts(
     cluster: &Cluster,
     symbol: Option<&str>,
-) -> HashMap<String, Pubkey> {
+) -> Result<HashMap<String, Pubkey>, Error> {
     let addr = get_pyth_mapping_account(cluster);
-    let mut akey = Pubkey::from_str(&addr).unwrap();
+    let mut akey = Pubkey::from_str(&addr)
+        .map_err(|_| Error::InvalidPayload(format!("invalid mapping account {}", addr)))?;
 
     let mut product_accounts = HashMap::new();
 
     loop {
         // get Mapping account from key
-        let map_data: &[u8] = &get_account_data(&cluster, &akey).await;
-        let map_acct = load_mapping(&map_data).unwrap();
+        let map_data: &[u8] = &get_account_data(&cluster, &akey).await?;
+        let map_acct = load_mapping(&map_data).map_err(|_| {
+            Error::InvalidPayload(format!("{} is not a Pyth mapping account", akey))
+        })?;
 
-        for prod_akey in &map_acct.products {
-            let prod_pkey = Pubkey::new(&prod_akey.val);
-            let prod_data: &[u8] = &get_account_data(&cluster, &prod_pkey).await;
+        // Only the first `num` entries are products, the rest of the table is empty
+        for prod_akey in map_acct.products.iter().take(map_acct.num as usize) {
+            let prod_pkey = Pubkey::new_from_array(prod_akey.val);
+            let prod_data: &[u8] = &get_account_data(&cluster, &prod_pkey).await?;
             let prod_acct = match load_product(&prod_data) {
                 Ok(prod_acct) => prod_acct,
                 Err(_) => break,
             };
 
-            // print key and reference data for this Product
-            // println!("prod_pkey .. {:?}", prod_pkey);
-            let mut pit = (&prod_acct.attr[..]).iter();
-
-            let _ = get_attr_str(&mut pit);
-            let val = get_attr_str(&mut pit);
-            // println!("  {:.<16} {}", key, val);
+            let val = get_product_symbol(prod_acct);
 
             // Valid?
             if prod_acct.px_acc.is_valid() {
                 // Then keep it
-                product_accounts.insert(val.clone(), Pubkey::new(&prod_acct.px_acc.val));
+                product_accounts.insert(val.clone(), Pubkey::new_from_array(prod_acct.px_acc.val));
             }
 
             // Found specific symbol?
@@ -88,26 +95,25 @@ pub(crate) async fn fetch_pyth_product_accounts(
         if !map_acct.next.is_valid() {
             break;
         }
-        akey = Pubkey::new(&map_acct.next.val);
+        akey = Pubkey::new_from_array(map_acct.next.val);
     }
 
-    product_accounts
+    Ok(product_accounts)
 }
 
 #[allow(dead_code)]
 pub(crate) async fn fetch_pyth_price_by_symbol(
     cluster: &Cluster,
     symbol: &str,
-) -> Option<PriceConf> {
+) -> Result<Option<PriceConf>, Error> {
     // Get product account
-    let px_pkeys = fetch_pyth_product_accounts(&cluster, Some(symbol)).await;
+    let px_pkeys = fetch_pyth_product_accounts(&cluster, Some(symbol)).await?;
 
     // Guard none px_pkey
-    if !px_pkeys.contains_key(symbol) {
-        return None;
-    }
-
-    let px_pkey = *px_pkeys.get(symbol).unwrap();
+    let px_pkey = match px_pkeys.get(symbol) {
+        Some(px_pkey) => *px_pkey,
+        None => return Ok(None),
+    };
 
     // Get price
     fetch_pyth_price_by_pubkey(&cluster, &px_pkey).await
@@ -116,45 +122,110 @@ pub(crate) async fn fetch_pyth_price_by_symbol(
 pub(crate) async fn fetch_pyth_price_by_pubkey(
     cluster: &Cluster,
     px_pkey: &Pubkey,
-) -> Option<PriceConf> {
+) -> Result<Option<PriceConf>, Error> {
     let mut current_price;
     let mut px_pkey = *px_pkey;
     loop {
-        let pd: &[u8] = &get_account_data(&cluster, &px_pkey).await;
-        let pa = load_price(&pd).unwrap();
+        let pd: &[u8] = &get_account_data(&cluster, &px_pkey).await?;
+        let pa = load_price(&pd).map_err(|_| {
+            Error::InvalidPayload(format!("{} is not a Pyth price account", px_pkey))
+        })?;
 
         current_price = pa.get_current_price();
 
         // go to next price account in list
         if pa.next.is_valid() {
-            px_pkey = Pubkey::new(&pa.next.val);
+            px_pkey = Pubkey::new_from_array(pa.next.val);
         } else {
             break;
         }
     }
 
-    current_price
+    Ok(current_price)
+}
+
+/// Current prices of many price accounts, in the order of `px_pkeys`.
+///
+/// Accounts are fetched with one `getMultipleAccounts` per hop along their `next` lists.
+pub(crate) async fn fetch_pyth_prices_by_pubkeys(
+    cluster: &Cluster,
+    px_pkeys: &[Pubkey],
+) -> Result<Vec<Option<PriceConf>>, Error> {
+    let mut prices = vec![None; px_pkeys.len()];
+    // (index in px_pkeys, account to fetch)
+    let mut pending: Vec<(usize, Pubkey)> = px_pkeys.iter().copied().enumerate().collect();
+
+    while !pending.is_empty() {
+        let pubkeys: Vec<Pubkey> = pending.iter().map(|(_, pubkey)| *pubkey).collect();
+        let accounts = get_multiple_account_data(cluster, &pubkeys).await?;
+
+        let mut next_pending = Vec::new();
+        for ((index, _), data) in pending.iter().zip(accounts.iter()) {
+            let pa = match data.as_deref().map(load_price) {
+                Some(Ok(pa)) => pa,
+                _ => continue,
+            };
+
+            prices[*index] = pa.get_current_price();
+
+            // go to next price account in list
+            if pa.next.is_valid() {
+                next_pending.push((*index, Pubkey::new_from_array(pa.next.val)));
+            }
+        }
+        pending = next_pending;
+    }
+
+    Ok(prices)
+}
+
+#[cfg(test)]
+use crate::testing::{pyth_devnet_fixtures, start_rpc_server, MockServer};
+
+/// Aggregate price, confidence and exponent held by the price account fixtures.
+#[cfg(test)]
+const SOL_USD: PriceConf = PriceConf {
+    price: 14_153_000_000,
+    conf: 6_500_000,
+    expo: -8,
+};
+
+#[cfg(test)]
+const BTC_USD: PriceConf = PriceConf {
+    price: 4_213_512_500_000,
+    conf: 2_150_000_000,
+    expo: -8,
+};
+
+#[cfg(test)]
+async fn start_devnet_pyth() -> (MockServer, Cluster) {
+    let server = start_rpc_server(pyth_devnet_fixtures()).await;
+    let cluster = Cluster::Custom(server.url());
+
+    (server, cluster)
 }
 
 #[cfg(test)]
 #[tokio::test]
 async fn test_fetch_pyth_product_accounts() {
-    let cluster = Cluster::Devnet;
-    let product_accounts = fetch_pyth_product_accounts(&cluster, None).await;
+    let (_server, cluster) = start_devnet_pyth().await;
+    let product_accounts = fetch_pyth_product_accounts(&cluster, None).await.unwrap();
 
-    println!("product_accounts: {:?}", product_accounts);
-    assert_eq!(product_accounts.is_empty(), false);
-    assert!(product_accounts.capacity() > 1);
+    // The mapping may list more products than were recorded with prices
+    for symbol in ["Crypto.BTC/USD", "Crypto.ETH/USD", "Crypto.SOL/USD"].iter() {
+        assert!(product_accounts.contains_key(*symbol), "{} missing", symbol);
+    }
 }
 
 #[cfg(test)]
 #[tokio::test]
 async fn test_fetch_pyth_product_account_by_symbol() {
-    let cluster = Cluster::Devnet;
+    let (_server, cluster) = start_devnet_pyth().await;
     let symbol = "Crypto.SOL/USD";
-    let product_account = fetch_pyth_product_account_by_symbol(&cluster, symbol).await;
+    let product_account = fetch_pyth_product_account_by_symbol(&cluster, symbol)
+        .await
+        .unwrap();
 
-    println!("product_account: {:?}", product_account);
     assert_eq!(
         product_account.unwrap().to_string(),
         "J83w4HKfqxwcq3BEMMkPFSppX3gqekLyLJBexebFVkix".to_string()
@@ -164,24 +235,63 @@ async fn test_fetch_pyth_product_account_by_symbol() {
 #[cfg(test)]
 #[tokio::test]
 async fn test_fetch_pyth_price_by_symbol() {
-    let cluster = Cluster::Devnet;
+    let (_server, cluster) = start_devnet_pyth().await;
     let symbol = "Crypto.SOL/USD";
-    let current_price = fetch_pyth_price_by_symbol(&cluster, symbol).await;
+    let current_price = fetch_pyth_price_by_symbol(&cluster, symbol).await.unwrap();
 
-    println!("current_price: {:?}", current_price);
-    assert_ne!(current_price, None);
+    assert_eq!(current_price, Some(SOL_USD));
 }
 
 #[cfg(test)]
 #[tokio::test]
 async fn test_fetch_pyth_price_by_pubkey() {
-    let cluster = Cluster::Devnet;
-    // Mocked SOL/USD
+    let (server, cluster) = start_devnet_pyth().await;
+    // SOL/USD
     let address = Pubkey::from_str("J83w4HKfqxwcq3BEMMkPFSppX3gqekLyLJBexebFVkix").unwrap();
 
-    // Fetch price from pyth
-    let product_account = fetch_pyth_price_by_pubkey(&cluster, &address).await;
+    let price = fetch_pyth_price_by_pubkey(&cluster, &address)
+        .await
+        .unwrap();
+
+    assert_eq!(price, Some(SOL_USD));
+    assert!(server.requests()[0].body.contains("getAccountInfo"));
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_fetch_pyth_prices_by_pubkeys() {
+    let (server, cluster) = start_devnet_pyth().await;
+    let px_pkeys = [
+        "HovQMDrbAgAYPCmHVSrezcSmkMtXSSUsLDFANExrZh2J",
+        "J83w4HKfqxwcq3BEMMkPFSppX3gqekLyLJBexebFVkix",
+        // Not a price account
+        "BmA9Z6FjioHJPpjT39QazZyhDRUdZy2ezwx4GiDdE2u2",
+    ]
+    .iter()
+    .map(|pubkey| Pubkey::from_str(pubkey).unwrap())
+    .collect::<Vec<_>>();
+
+    let prices = fetch_pyth_prices_by_pubkeys(&cluster, &px_pkeys)
+        .await
+        .unwrap();
+
+    assert_eq!(prices, vec![Some(BTC_USD), Some(SOL_USD), None]);
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_fetch_pyth_price_errors() {
+    let (_server, cluster) = start_devnet_pyth().await;
+    // Not recorded, the account doesn't exist
+    let missing = fetch_pyth_price_by_pubkey(&cluster, &Pubkey::default()).await;
+    assert!(matches!(missing, Err(Error::NotFound(_))));
+    // The mapping account
+    let mapping = Pubkey::from_str("BmA9Z6FjioHJPpjT39QazZyhDRUdZy2ezwx4GiDdE2u2").unwrap();
+    let not_price = fetch_pyth_price_by_pubkey(&cluster, &mapping).await;
+    assert!(matches!(not_price, Err(Error::InvalidPayload(_))));
 
-    println!("product_account: {:?}", product_account);
-    assert_ne!(product_account, None);
+    let down = MockServer::start(|_request| (503, String::new())).await;
+    let result = fetch_pyth_product_accounts(&Cluster::Custom(down.url()), None).await;
+    assert!(matches!(result, Err(Error::RequestFailed(_))));
 }
@@ -1,2 +1,6 @@
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) mod fixture;
 pub(crate) mod pubkey;
+pub(crate) mod reader;
+pub(crate) mod token;
 pub(crate) mod web3;
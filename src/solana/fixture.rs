@@ -0,0 +1,120 @@
+//! Accounts recorded from a cluster, as stored under `tests/fixtures`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{
+    error::Error,
+    solana::{
+        pubkey::Pubkey,
+        web3::{get_account_info, Cluster},
+    },
+};
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub(crate) struct AccountFixture {
+    pub(crate) pubkey: String,
+    pub(crate) owner: String,
+    pub(crate) lamports: u64,
+    /// Base64 account data.
+    pub(crate) data: String,
+    pub(crate) slot: u64,
+}
+
+#[allow(dead_code)]
+impl AccountFixture {
+    /// Account built in a test rather than recorded, at slot 0.
+    pub(crate) fn new(pubkey: &str, owner: &str, lamports: u64, data: &[u8]) -> AccountFixture {
+        AccountFixture {
+            pubkey: pubkey.to_string(),
+            owner: owner.to_string(),
+            lamports,
+            data: base64::encode(data),
+            slot: 0,
+        }
+    }
+
+    pub(crate) fn from_json(json: &str) -> AccountFixture {
+        serde_json::from_str(json).unwrap()
+    }
+
+    /// Fixture of `pubkey` from a `base64` encoded `getAccountInfo` response, `None` when the
+    /// account doesn't exist.
+    pub(crate) fn from_account_info(pubkey: &str, response: &Value) -> Option<AccountFixture> {
+        let value = &response["result"]["value"];
+
+        Some(AccountFixture {
+            pubkey: pubkey.to_string(),
+            owner: value["owner"].as_str()?.to_string(),
+            lamports: value["lamports"].as_u64()?,
+            data: value["data"][0].as_str()?.to_string(),
+            slot: response["result"]["context"]["slot"].as_u64()?,
+        })
+    }
+
+    /// Account as a `base64` encoded JSON-RPC result.
+    pub(crate) fn rpc_value(&self) -> Value {
+        json!({
+            "data": [self.data, "base64"],
+            "executable": false,
+            "lamports": self.lamports,
+            "owner": self.owner,
+            "rentEpoch": 0,
+        })
+    }
+
+    pub(crate) fn data(&self) -> Vec<u8> {
+        base64::decode(&self.data).unwrap()
+    }
+}
+
+/// Record `pubkey` as it is on `cluster` now.
+#[allow(dead_code)]
+pub(crate) async fn fetch_account_fixture(
+    cluster: &Cluster,
+    pubkey: &Pubkey,
+) -> Result<AccountFixture, Error> {
+    let pubkey = pubkey.to_string();
+    let response = get_account_info(cluster, &pubkey).await?;
+
+    AccountFixture::from_account_info(&pubkey, &response)
+        .ok_or_else(|| Error::NotFound(format!("account {}", pubkey)))
+}
+
+#[cfg(test)]
+#[test]
+fn test_fixture_round_trips_account_info() {
+    let fixture =
+        AccountFixture::from_json(include_str!("../../tests/fixtures/marinade/state.json"));
+    let response = json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "result": { "context": { "slot": fixture.slot }, "value": fixture.rpc_value() },
+    });
+
+    assert_eq!(
+        AccountFixture::from_account_info(&fixture.pubkey, &response),
+        Some(fixture.clone())
+    );
+    assert_eq!(
+        AccountFixture::from_account_info(
+            &fixture.pubkey,
+            &json!({ "result": { "context": { "slot": 1 }, "value": null } })
+        ),
+        None
+    );
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_fetch_account_fixture_errors() {
+    use crate::testing::MockServer;
+
+    let down = MockServer::start(|_request| (503, String::new())).await;
+    let result = fetch_account_fixture(&Cluster::Custom(down.url()), &Pubkey::default()).await;
+    assert!(matches!(result, Err(Error::RequestFailed(_))));
+
+    let garbled = MockServer::start(|_request| (200, "<html>".to_string())).await;
+    let result = fetch_account_fixture(&Cluster::Custom(garbled.url()), &Pubkey::default()).await;
+    assert!(matches!(result, Err(Error::RequestFailed(_))));
+}
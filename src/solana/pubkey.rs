@@ -1,15 +1,25 @@
 #![allow(clippy::integer_arithmetic)]
 use {
+    serde::{de, Deserialize, Deserializer, Serialize, Serializer},
+    sha2::{Digest, Sha256},
     std::{
         convert::{Infallible, TryFrom},
-        fmt, mem,
+        fmt,
         str::FromStr,
     },
     thiserror::Error,
 };
 
+/// Number of bytes in a pubkey
+pub(crate) const PUBKEY_BYTES: usize = 32;
 /// Maximum string length of a base58 encoded pubkey
 const MAX_BASE58_LEN: usize = 44;
+/// Maximum length of derived `Pubkey` seed
+pub(crate) const MAX_SEED_LEN: usize = 32;
+/// Maximum number of seeds
+pub(crate) const MAX_SEEDS: usize = 16;
+/// Appended to the seeds of a program derived address
+const PDA_MARKER: &[u8; 21] = b"ProgramDerivedAddress";
 
 #[derive(Error, Debug, Clone, PartialEq)]
 pub enum PubkeyError {
@@ -32,7 +42,7 @@ impl From<u64> for PubkeyError {
 
 #[repr(transparent)]
 #[derive(Clone, Copy, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
-pub(crate) struct Pubkey(pub(crate) [u8; 32]);
+pub(crate) struct Pubkey(pub(crate) [u8; PUBKEY_BYTES]);
 
 #[derive(Error, Debug, Clone, PartialEq)]
 pub enum ParsePubkeyError {
@@ -58,11 +68,16 @@ impl FromStr for Pubkey {
         let pubkey_vec = bs58::decode(s)
             .into_vec()
             .map_err(|_| ParsePubkeyError::Invalid)?;
-        if pubkey_vec.len() != mem::size_of::<Pubkey>() {
-            Err(ParsePubkeyError::WrongSize)
-        } else {
-            Ok(Pubkey::new(&pubkey_vec))
-        }
+        Pubkey::try_from(&pubkey_vec[..])
+    }
+}
+
+impl TryFrom<&[u8]> for Pubkey {
+    type Error = ParsePubkeyError;
+    fn try_from(pubkey: &[u8]) -> Result<Self, Self::Error> {
+        <[u8; PUBKEY_BYTES]>::try_from(pubkey)
+            .map(Pubkey::new_from_array)
+            .map_err(|_| ParsePubkeyError::WrongSize)
     }
 }
 
@@ -73,13 +88,70 @@ impl TryFrom<&str> for Pubkey {
     }
 }
 
+impl From<[u8; PUBKEY_BYTES]> for Pubkey {
+    fn from(pubkey: [u8; PUBKEY_BYTES]) -> Self {
+        Pubkey::new_from_array(pubkey)
+    }
+}
+
 impl Pubkey {
-    pub fn new(pubkey_vec: &[u8]) -> Self {
-        Self(
-            <[u8; 32]>::try_from(<&[u8]>::clone(&pubkey_vec))
-                .expect("Slice must be the same length as a Pubkey"),
-        )
+    pub(crate) const fn new_from_array(pubkey_array: [u8; PUBKEY_BYTES]) -> Self {
+        Self(pubkey_array)
     }
+
+    #[allow(dead_code)]
+    pub(crate) fn to_bytes(self) -> [u8; PUBKEY_BYTES] {
+        self.0
+    }
+
+    /// Whether the key is a point on the ed25519 curve, so has a private key.
+    #[allow(dead_code)]
+    pub(crate) fn is_on_curve(&self) -> bool {
+        bytes_are_curve_point(&self.0)
+    }
+
+    /// Address of `seeds` under `program_id`, which must not be on the curve.
+    pub(crate) fn create_program_address(
+        seeds: &[&[u8]],
+        program_id: &Pubkey,
+    ) -> Result<Pubkey, PubkeyError> {
+        if seeds.len() > MAX_SEEDS || seeds.iter().any(|seed| seed.len() > MAX_SEED_LEN) {
+            return Err(PubkeyError::MaxSeedLengthExceeded);
+        }
+
+        let mut hasher = Sha256::new();
+        for seed in seeds {
+            hasher.update(seed);
+        }
+        hasher.update(program_id);
+        hasher.update(PDA_MARKER);
+        let hash: [u8; PUBKEY_BYTES] = hasher.finalize().into();
+
+        if bytes_are_curve_point(&hash) {
+            return Err(PubkeyError::InvalidSeeds);
+        }
+        Ok(Pubkey::new_from_array(hash))
+    }
+
+    /// First program address of `seeds` and a bump seed, counting down from 255, and that bump.
+    pub(crate) fn find_program_address(
+        seeds: &[&[u8]],
+        program_id: &Pubkey,
+    ) -> Option<(Pubkey, u8)> {
+        (0..=u8::MAX).rev().find_map(|bump| {
+            let bump_seed = [bump];
+            let mut seeds_with_bump = seeds.to_vec();
+            seeds_with_bump.push(&bump_seed);
+
+            Pubkey::create_program_address(&seeds_with_bump, program_id)
+                .ok()
+                .map(|address| (address, bump))
+        })
+    }
+}
+
+fn bytes_are_curve_point(bytes: &[u8; PUBKEY_BYTES]) -> bool {
+    ed25519_dalek::PublicKey::from_bytes(bytes).is_ok()
 }
 
 impl AsRef<[u8]> for Pubkey {
@@ -105,3 +177,89 @@ impl fmt::Display for Pubkey {
         write!(f, "{}", bs58::encode(self.0).into_string())
     }
 }
+
+impl Serialize for Pubkey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Pubkey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Pubkey::from_str(&s).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_create_program_address() {
+    let exceeded_seed = &[127; MAX_SEED_LEN + 1];
+    let max_seed = &[0; MAX_SEED_LEN];
+    let program_id = Pubkey::from_str("BPFLoaderUpgradeab1e11111111111111111111111").unwrap();
+    let public_key = Pubkey::from_str("SeedPubey1111111111111111111111111111111111").unwrap();
+
+    assert_eq!(
+        Pubkey::create_program_address(&[exceeded_seed], &program_id),
+        Err(PubkeyError::MaxSeedLengthExceeded)
+    );
+    assert!(Pubkey::create_program_address(&[max_seed], &program_id).is_ok());
+
+    let cases: [(&[&[u8]], &str); 4] = [
+        (&[b"", &[1]], "BwqrghZA2htAcqq8dzP1WDAhTXYTYWj7CHxF5j7TDBAe"),
+        (
+            &["☉".as_ref(), &[0]],
+            "13yWmRpaTR4r5nAktwLqMpRNr28tnVUZw26rTvPSSB19",
+        ),
+        (
+            &[b"Talking", b"Squirrels"],
+            "2fnQrngrQT4SeLcdToJAD96phoEjNL2man2kfRLCASVk",
+        ),
+        (
+            &[public_key.as_ref(), &[1]],
+            "976ymqVnfE32QFe6NfGDctSvVa36LWnvYxhU6G2232YL",
+        ),
+    ];
+    for (seeds, address) in cases.iter() {
+        assert_eq!(
+            Pubkey::create_program_address(seeds, &program_id).unwrap(),
+            Pubkey::from_str(address).unwrap()
+        );
+    }
+
+    let (address, bump) = Pubkey::find_program_address(&[b"Lil'", b"Bits"], &program_id).unwrap();
+    assert_eq!(
+        Pubkey::create_program_address(&[b"Lil'", b"Bits", &[bump]], &program_id),
+        Ok(address)
+    );
+    assert!(!address.is_on_curve());
+
+    let secret = ed25519_dalek::SecretKey::from_bytes(&[7; 32]).unwrap();
+    let wallet = ed25519_dalek::PublicKey::from(&secret).to_bytes();
+    assert!(Pubkey::new_from_array(wallet).is_on_curve());
+}
+
+#[cfg(test)]
+#[test]
+fn test_bytes_and_serde() {
+    let text = "J83w4HKfqxwcq3BEMMkPFSppX3gqekLyLJBexebFVkix";
+    let pubkey = Pubkey::from_str(text).unwrap();
+
+    assert_eq!(Pubkey::try_from(&pubkey.to_bytes()[..]), Ok(pubkey));
+    assert_eq!(
+        Pubkey::try_from(&[0u8; 31][..]),
+        Err(ParsePubkeyError::WrongSize)
+    );
+    assert_eq!(Pubkey::from_str("1"), Err(ParsePubkeyError::WrongSize));
+    assert_eq!(Pubkey::from_str("0OIl"), Err(ParsePubkeyError::Invalid));
+
+    assert_eq!(
+        serde_json::to_value(pubkey).unwrap(),
+        serde_json::json!(text)
+    );
+    assert_eq!(
+        serde_json::from_value::<Pubkey>(serde_json::json!(text)).unwrap(),
+        pubkey
+    );
+    assert!(serde_json::from_str::<Pubkey>("\"not-a-pubkey\"").is_err());
+}
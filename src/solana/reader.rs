@@ -0,0 +1,70 @@
+use std::convert::TryInto;
+
+use crate::{
+    error::Error,
+    solana::pubkey::{Pubkey, PUBKEY_BYTES},
+};
+
+/// Reader over packed little endian account fields, as laid out by Borsh and Solana programs.
+pub(crate) struct AccountReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> AccountReader<'a> {
+    pub(crate) fn new(data: &'a [u8], offset: usize) -> Self {
+        AccountReader { data, offset }
+    }
+
+    pub(crate) fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        let bytes = self
+            .data
+            .get(self.offset..self.offset + len)
+            .ok_or_else(|| Error::InvalidPayload("account data is too short".to_string()))?;
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    pub(crate) fn skip(&mut self, len: usize) -> Result<(), Error> {
+        self.take(len).map(|_| ())
+    }
+
+    pub(crate) fn u8(&mut self) -> Result<u8, Error> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub(crate) fn u16(&mut self) -> Result<u16, Error> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32, Error> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    pub(crate) fn u64(&mut self) -> Result<u64, Error> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    pub(crate) fn u128(&mut self) -> Result<u128, Error> {
+        Ok(u128::from_le_bytes(self.take(16)?.try_into().unwrap()))
+    }
+
+    pub(crate) fn pubkey(&mut self) -> Result<Pubkey, Error> {
+        Ok(Pubkey::new_from_array(
+            self.take(PUBKEY_BYTES)?.try_into().unwrap(),
+        ))
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_account_reader() {
+    let data = [1u8, 2, 0, 3, 0, 0, 0, 9];
+    let mut reader = AccountReader::new(&data, 0);
+
+    assert_eq!(reader.u8().unwrap(), 1);
+    assert_eq!(reader.u16().unwrap(), 2);
+    assert_eq!(reader.u32().unwrap(), 3);
+    assert!(reader.u64().is_err());
+    assert_eq!(reader.u8().unwrap(), 9);
+}
@@ -0,0 +1,86 @@
+use std::str::FromStr;
+
+use crate::{
+    error::Error,
+    solana::{pubkey::Pubkey, reader::AccountReader},
+};
+
+/// SPL Token program.
+pub(crate) const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+
+/// SPL Token-2022 program, its accounts and mints start with the SPL Token layout.
+pub(crate) const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+
+/// SPL Associated Token Account program.
+pub(crate) const ASSOCIATED_TOKEN_PROGRAM_ID: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
+
+/// Associated token account of `wallet` for `mint`.
+#[allow(dead_code)]
+pub(crate) fn get_associated_token_address(wallet: &Pubkey, mint: &Pubkey) -> Pubkey {
+    let token_program = Pubkey::from_str(TOKEN_PROGRAM_ID).unwrap();
+    let program_id = Pubkey::from_str(ASSOCIATED_TOKEN_PROGRAM_ID).unwrap();
+
+    Pubkey::find_program_address(
+        &[wallet.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &program_id,
+    )
+    .map(|(address, _)| address)
+    .expect("no viable bump seed")
+}
+
+/// Fields of an SPL Token or Token-2022 account we need, `amount` in the mint's smallest unit.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct TokenAccount {
+    pub(crate) mint: Pubkey,
+    pub(crate) owner: Pubkey,
+    pub(crate) amount: u64,
+}
+
+impl TokenAccount {
+    pub(crate) fn decode(data: &[u8]) -> Result<TokenAccount, Error> {
+        let mut reader = AccountReader::new(data, 0);
+
+        Ok(TokenAccount {
+            mint: reader.pubkey()?,
+            owner: reader.pubkey()?,
+            amount: reader.u64()?,
+        })
+    }
+}
+
+/// Decimals of an SPL Token or Token-2022 mint account.
+pub(crate) fn decode_mint_decimals(data: &[u8]) -> Result<u8, Error> {
+    // mint_authority: COption<Pubkey>, supply
+    let mut reader = AccountReader::new(data, 4 + 32 + 8);
+    reader.u8()
+}
+
+#[cfg(test)]
+#[test]
+fn test_decode_token_account_and_mint() {
+    let mut account = vec![1u8; 32];
+    account.extend_from_slice(&[2u8; 32]);
+    account.extend_from_slice(&1_500_000u64.to_le_bytes());
+    account.resize(165, 0);
+
+    let token_account = TokenAccount::decode(&account).unwrap();
+    assert_eq!(token_account.mint, Pubkey([1u8; 32]));
+    assert_eq!(token_account.owner, Pubkey([2u8; 32]));
+    assert_eq!(token_account.amount, 1_500_000);
+
+    let mut mint = vec![0u8; 82];
+    mint[44] = 6;
+    assert_eq!(decode_mint_decimals(&mint).unwrap(), 6);
+    assert!(decode_mint_decimals(&mint[..44]).is_err());
+}
+
+#[cfg(test)]
+#[test]
+fn test_associated_token_address() {
+    let wallet = Pubkey::from_str("9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM").unwrap();
+    let mint = Pubkey::from_str("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v").unwrap();
+
+    let address = get_associated_token_address(&wallet, &mint);
+    assert!(!address.is_on_curve());
+    assert_ne!(address, get_associated_token_address(&mint, &wallet));
+}
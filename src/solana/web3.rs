@@ -1,23 +1,62 @@
+use serde::Deserialize;
 use serde_json::json;
+use std::fmt;
+use std::str::FromStr;
 
-use crate::solana::pubkey::Pubkey;
+use crate::{error::Error, solana::pubkey::Pubkey};
 
 #[allow(dead_code)]
+#[derive(Clone, Debug, PartialEq)]
 pub(crate) enum Cluster {
     Development,
     Devnet,
     Testnet,
     MainnetBeta,
+    /// JSON-RPC endpoint serving devnet accounts, e.g. a local mock in tests.
+    Custom(String),
 }
 
-fn get_cluster_api_url(cluster: &Cluster) -> &'static str {
-    match cluster {
+impl fmt::Display for Cluster {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Cluster::Development => "development",
+            Cluster::Devnet => "devnet",
+            Cluster::Testnet => "testnet",
+            Cluster::MainnetBeta => "mainnet-beta",
+            Cluster::Custom(url) => url,
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Cluster {
+    type Err = Error;
+
+    /// Cluster name as shown by `Display`, or the url of any other JSON-RPC endpoint.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "development" => Ok(Cluster::Development),
+            "devnet" => Ok(Cluster::Devnet),
+            "testnet" => Ok(Cluster::Testnet),
+            "mainnet-beta" => Ok(Cluster::MainnetBeta),
+            url if url.starts_with("http://") || url.starts_with("https://") => {
+                Ok(Cluster::Custom(url.to_string()))
+            }
+            _ => Err(Error::InvalidPayload(format!("unknown cluster {}", s))),
+        }
+    }
+}
+
+fn get_cluster_api_url(cluster: &Cluster) -> String {
+    let url = match cluster {
         // *self has type Direction
         Cluster::Development => "https://api.devnet.solana.com",
         Cluster::Devnet => "https://api.devnet.solana.com",
         Cluster::Testnet => "https://api.testnet.solana.com",
         Cluster::MainnetBeta => "https://api.mainnet-beta.solana.com",
-    }
+        Cluster::Custom(url) => url,
+    };
+    url.to_string()
 }
 
 fn build_request_json(id: u64, method: &str, params: serde_json::Value) -> serde_json::Value {
@@ -30,34 +69,193 @@ fn build_request_json(id: u64, method: &str, params: serde_json::Value) -> serde
     })
 }
 
-pub(crate) async fn get_account_info(
+/// Response of `method` on `cluster`, an error when the request or the RPC fails.
+async fn call_rpc(
     cluster: &Cluster,
-    pubkey_string: &String,
-) -> serde_json::Value {
+    method: &str,
+    params: serde_json::Value,
+) -> Result<serde_json::Value, Error> {
     let client = reqwest::Client::new();
     let request_id: u64 = 0;
-    let method = "getAccountInfo";
-    let params = json!([pubkey_string,{
-    "encoding": "base64"
-    }]);
     let request_json = build_request_json(request_id, method, params).to_string();
 
     let response = client
-        .post(get_cluster_api_url(&cluster))
+        .post(get_cluster_api_url(cluster))
         .header("Content-Type", "application/json")
         .body(request_json)
         .send()
-        .await
-        .unwrap();
+        .await?
+        .error_for_status()?;
+    let map_data: serde_json::Value = response.json().await?;
+
+    match map_data.get("error") {
+        Some(error) => Err(Error::RpcFailed(
+            error["message"].as_str().unwrap_or_default().to_string(),
+        )),
+        None => Ok(map_data),
+    }
+}
+
+pub(crate) async fn get_account_info(
+    cluster: &Cluster,
+    pubkey_string: &String,
+) -> Result<serde_json::Value, Error> {
+    let params = json!([pubkey_string,{
+    "encoding": "base64"
+    }]);
+
+    call_rpc(cluster, "getAccountInfo", params).await
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct EpochInfo {
+    pub(crate) epoch: u64,
+    pub(crate) slot_index: u64,
+    pub(crate) slots_in_epoch: u64,
+    pub(crate) absolute_slot: u64,
+}
+
+impl EpochInfo {
+    /// Progress through the current epoch, 0 to 1.
+    pub(crate) fn progress(&self) -> f64 {
+        self.slot_index as f64 / self.slots_in_epoch.max(1) as f64
+    }
+}
+
+pub(crate) async fn get_epoch_info(cluster: &Cluster) -> Option<EpochInfo> {
+    let map_data = call_rpc(cluster, "getEpochInfo", json!([])).await.ok()?;
+
+    serde_json::from_value(map_data["result"].clone()).ok()
+}
 
-    response.json().await.unwrap()
+/// Data of `pubkey`, `NotFound` when the account doesn't exist.
+pub(crate) async fn get_account_data(cluster: &Cluster, pubkey: &Pubkey) -> Result<Vec<u8>, Error> {
+    let map_data = get_account_info(&cluster, &pubkey.to_string()).await?;
+    let value = &map_data["result"]["value"];
+    if value.is_null() {
+        return Err(Error::NotFound(format!("account {}", pubkey)));
+    }
+    let b64 = value["data"][0].as_str().ok_or_else(|| {
+        Error::RpcFailed(format!("getAccountInfo returned no data for {}", pubkey))
+    })?;
+
+    base64::decode(b64)
+        .map_err(|_| Error::InvalidPayload(format!("data of {} is not base64", pubkey)))
+}
+
+/// Max accounts per `getMultipleAccounts` request.
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+/// `getMultipleAccounts` result, an error when the request or the RPC fails.
+pub(crate) async fn get_multiple_accounts(
+    cluster: &Cluster,
+    pubkey_strings: &[String],
+) -> Result<serde_json::Value, Error> {
+    let params = json!([pubkey_strings,{
+    "encoding": "base64"
+    }]);
+
+    call_rpc(cluster, "getMultipleAccounts", params).await
 }
 
-pub(crate) async fn get_account_data(cluster: &Cluster, pubkey: &Pubkey) -> Vec<u8> {
-    let map_data = get_account_info(&cluster, &pubkey.to_string()).await;
-    let result = map_data["result"]["value"]["data"][0].clone();
-    let foo_str = result.as_str().unwrap();
-    let b64 = base64::decode(foo_str);
+/// Data of each account in the order of `pubkeys`, `None` for an account that doesn't exist.
+pub(crate) async fn get_multiple_account_data(
+    cluster: &Cluster,
+    pubkeys: &[Pubkey],
+) -> Result<Vec<Option<Vec<u8>>>, Error> {
+    let mut accounts = Vec::with_capacity(pubkeys.len());
+
+    for chunk in pubkeys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        let pubkey_strings: Vec<String> = chunk.iter().map(|pubkey| pubkey.to_string()).collect();
+        let map_data = get_multiple_accounts(cluster, &pubkey_strings).await?;
+        let values = map_data["result"]["value"]
+            .as_array()
+            .filter(|values| values.len() == chunk.len())
+            .ok_or_else(|| {
+                Error::RpcFailed("getMultipleAccounts returned no accounts".to_string())
+            })?;
+
+        accounts.extend(values.iter().map(|value| {
+            value["data"][0]
+                .as_str()
+                .and_then(|b64| base64::decode(b64).ok())
+        }));
+    }
+
+    Ok(accounts)
+}
+
+/// SOL balance in lamports, an error when the request or the RPC fails.
+pub(crate) async fn get_balance(cluster: &Cluster, pubkey: &Pubkey) -> Result<u64, Error> {
+    let params = json!([pubkey.to_string()]);
+    let map_data = call_rpc(cluster, "getBalance", params).await?;
+
+    map_data["result"]["value"]
+        .as_u64()
+        .ok_or_else(|| Error::RpcFailed("getBalance returned no balance".to_string()))
+}
+
+/// Data of every account owned by `owner` under `program_id`, e.g. SPL token accounts.
+pub(crate) async fn get_token_accounts_by_owner(
+    cluster: &Cluster,
+    owner: &Pubkey,
+    program_id: &str,
+) -> Result<Vec<Vec<u8>>, Error> {
+    let params = json!([owner.to_string(), {
+    "programId": program_id
+    }, {
+    "encoding": "base64"
+    }]);
+    let map_data = call_rpc(cluster, "getTokenAccountsByOwner", params).await?;
+
+    let accounts = map_data["result"]["value"]
+        .as_array()
+        .ok_or_else(|| {
+            Error::RpcFailed("getTokenAccountsByOwner returned no accounts".to_string())
+        })?
+        .iter()
+        .filter_map(|value| value["account"]["data"][0].as_str())
+        .filter_map(|b64| base64::decode(b64).ok())
+        .collect();
+
+    Ok(accounts)
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_get_multiple_account_data_errors() {
+    use crate::testing::MockServer;
+
+    let pubkeys = [Pubkey::default()];
+    let down = MockServer::start(|_request| (503, String::new())).await;
+    let result = get_multiple_account_data(&Cluster::Custom(down.url()), &pubkeys).await;
+    assert!(matches!(result, Err(Error::RequestFailed(_))));
+
+    let limited = MockServer::start(|_request| {
+        let body = r#"{"jsonrpc":"2.0","id":0,"error":{"code":429,"message":"Too many requests"}}"#;
+        (200, body.to_string())
+    })
+    .await;
+    let result = get_multiple_account_data(&Cluster::Custom(limited.url()), &pubkeys).await;
+    assert!(matches!(result, Err(Error::RpcFailed(message)) if message == "Too many requests"));
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_wallet_reads_report_errors() {
+    use crate::testing::MockServer;
+
+    let limited = MockServer::start(|_request| {
+        let body = r#"{"jsonrpc":"2.0","id":0,"error":{"code":429,"message":"Too many requests"}}"#;
+        (200, body.to_string())
+    })
+    .await;
+    let cluster = Cluster::Custom(limited.url());
+    let owner = Pubkey::default();
 
-    b64.unwrap()
+    let balance = get_balance(&cluster, &owner).await;
+    assert!(matches!(balance, Err(Error::RpcFailed(message)) if message == "Too many requests"));
+    let accounts = get_token_accounts_by_owner(&cluster, &owner, "Tokenkeg").await;
+    assert!(matches!(accounts, Err(Error::RpcFailed(message)) if message == "Too many requests"));
 }
@@ -0,0 +1,62 @@
+//! Work finished after the response is sent, kept alive with `waitUntil` in the worker.
+
+use std::{future::Future, pin::Pin, rc::Rc};
+
+use crate::{error::Error, kv::bindings::Bindings};
+
+type TaskFn = dyn FnOnce(Rc<Bindings>) -> Pin<Box<dyn Future<Output = Result<(), Error>>>>;
+
+/// Background job run with the worker's bindings once the response is returned.
+pub(crate) struct Task(Box<TaskFn>);
+
+impl Task {
+    pub(crate) fn new<F, Fut>(task: F) -> Self
+    where
+        F: FnOnce(Rc<Bindings>) -> Fut + 'static,
+        Fut: Future<Output = Result<(), Error>> + 'static,
+    {
+        Task(Box::new(move |bindings| Box::pin(task(bindings))))
+    }
+
+    pub(crate) async fn run(self, bindings: Rc<Bindings>) -> Result<(), Error> {
+        (self.0)(bindings).await
+    }
+}
+
+/// Run `tasks` one after another, all of them even when one fails, and return the first error.
+pub(crate) async fn run_tasks(tasks: Vec<Task>, bindings: Rc<Bindings>) -> Result<(), Error> {
+    let mut result = Ok(());
+    for task in tasks {
+        let outcome = task.run(bindings.clone()).await;
+        if result.is_ok() {
+            result = outcome;
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_run_tasks_in_order() {
+    use std::cell::RefCell;
+
+    let done = Rc::new(RefCell::new(Vec::new()));
+    let tasks = (1..=3)
+        .map(|n| {
+            let done = done.clone();
+            Task::new(move |_| async move {
+                done.borrow_mut().push(n);
+                match n {
+                    2 => Err(Error::NotFound(n.to_string())),
+                    _ => Ok(()),
+                }
+            })
+        })
+        .collect();
+
+    let result = run_tasks(tasks, Rc::new(Bindings::in_memory())).await;
+
+    assert!(matches!(result, Err(Error::NotFound(n)) if n == "2"));
+    assert_eq!(*done.borrow(), vec![1, 2, 3]);
+}
@@ -0,0 +1,234 @@
+//! Test support for the native test suite.
+
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    fs,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Request, Response, Server,
+};
+use serde_json::{json, Value};
+use tokio::sync::oneshot;
+
+pub(crate) use crate::solana::fixture::AccountFixture;
+use crate::solana::pubkey::Pubkey;
+
+pub(crate) mod e2e;
+
+#[derive(Clone, Debug)]
+pub(crate) struct RecordedRequest {
+    pub(crate) method: String,
+    pub(crate) path: String,
+    pub(crate) headers: HashMap<String, String>,
+    pub(crate) body: String,
+}
+
+type Handler = dyn Fn(&RecordedRequest) -> (u16, String) + Send + Sync;
+
+/// Local HTTP server answering every request with `handler` and recording what it received.
+pub(crate) struct MockServer {
+    addr: SocketAddr,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl MockServer {
+    pub(crate) async fn start<F>(handler: F) -> MockServer
+    where
+        F: Fn(&RecordedRequest) -> (u16, String) + Send + Sync + 'static,
+    {
+        let handler: Arc<Handler> = Arc::new(handler);
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+
+        let make_service = make_service_fn(move |_| {
+            let handler = handler.clone();
+            let recorded = recorded.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request: Request<Body>| {
+                    let handler = handler.clone();
+                    let recorded = recorded.clone();
+                    async move {
+                        let request = record(request).await;
+                        let (status, body) = handler(&request);
+                        recorded.lock().unwrap().push(request);
+
+                        Ok::<_, Infallible>(
+                            Response::builder()
+                                .status(status)
+                                .header("Content-Type", "application/json")
+                                .body(Body::from(body))
+                                .unwrap(),
+                        )
+                    }
+                }))
+            }
+        });
+
+        let (shutdown, shutdown_signal) = oneshot::channel::<()>();
+        let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(make_service);
+        let addr = server.local_addr();
+        tokio::spawn(server.with_graceful_shutdown(async {
+            shutdown_signal.await.ok();
+        }));
+
+        MockServer {
+            addr,
+            requests,
+            shutdown: Some(shutdown),
+        }
+    }
+
+    pub(crate) fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    pub(crate) fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            shutdown.send(()).ok();
+        }
+    }
+}
+
+async fn record(request: Request<Body>) -> RecordedRequest {
+    let (parts, body) = request.into_parts();
+    let body = hyper::body::to_bytes(body).await.unwrap_or_default();
+
+    RecordedRequest {
+        method: parts.method.to_string(),
+        path: parts
+            .uri
+            .path_and_query()
+            .map(|p| p.to_string())
+            .unwrap_or_default(),
+        headers: parts
+            .headers
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or_default().to_string()))
+            .collect(),
+        body: String::from_utf8_lossy(&body).to_string(),
+    }
+}
+
+/// Slots per epoch on mainnet-beta and devnet.
+const SLOTS_PER_EPOCH: u64 = 432_000;
+
+/// Local Solana JSON-RPC server answering from `fixtures`, point `Cluster::Custom` at its url.
+///
+/// Accounts without a fixture don't exist. `getTokenAccountsByOwner` finds the token accounts
+/// among them and `getEpochInfo` is the epoch of the latest recorded slot.
+pub(crate) async fn start_rpc_server(fixtures: Vec<AccountFixture>) -> MockServer {
+    let slot = fixtures
+        .iter()
+        .map(|fixture| fixture.slot)
+        .max()
+        .unwrap_or_default();
+    let accounts: HashMap<String, AccountFixture> = fixtures
+        .into_iter()
+        .map(|fixture| (fixture.pubkey.clone(), fixture))
+        .collect();
+
+    MockServer::start(move |request| {
+        let request: Value = serde_json::from_str(&request.body).unwrap_or_default();
+        let params = &request["params"];
+        let account = |pubkey: &Value| {
+            pubkey
+                .as_str()
+                .and_then(|pubkey| accounts.get(pubkey))
+                .map_or(Value::Null, AccountFixture::rpc_value)
+        };
+        // Token accounts keep their owner after the mint
+        let token_accounts = |owner: &Value, program_id: &Value| -> Value {
+            let owner = match owner.as_str().map(Pubkey::from_str) {
+                Some(Ok(owner)) => owner,
+                _ => return Value::Null,
+            };
+            let mut owned: Vec<&AccountFixture> = accounts
+                .values()
+                .filter(|fixture| Some(fixture.owner.as_str()) == program_id.as_str())
+                .filter(|fixture| fixture.data().get(32..64) == Some(owner.as_ref()))
+                .collect();
+            owned.sort_by(|a, b| a.pubkey.cmp(&b.pubkey));
+
+            owned
+                .iter()
+                .map(|fixture| json!({ "pubkey": fixture.pubkey, "account": fixture.rpc_value() }))
+                .collect()
+        };
+
+        let value = match request["method"].as_str() {
+            Some("getAccountInfo") => account(&params[0]),
+            Some("getMultipleAccounts") => match params[0].as_array() {
+                Some(pubkeys) => pubkeys.iter().map(account).collect(),
+                None => Value::Null,
+            },
+            Some("getBalance") => json!(account(&params[0])["lamports"].as_u64().unwrap_or(0)),
+            Some("getTokenAccountsByOwner") => token_accounts(&params[0], &params[1]["programId"]),
+            Some("getEpochInfo") => {
+                let result = json!({
+                    "epoch": slot / SLOTS_PER_EPOCH,
+                    "slotIndex": slot % SLOTS_PER_EPOCH,
+                    "slotsInEpoch": SLOTS_PER_EPOCH,
+                    "absoluteSlot": slot,
+                });
+                let body = json!({ "jsonrpc": "2.0", "id": request["id"], "result": result });
+                return (200, body.to_string());
+            }
+            _ => {
+                let error = json!({ "code": -32601, "message": "Method not found" });
+                let body = json!({ "jsonrpc": "2.0", "id": request["id"], "error": error });
+                return (200, body.to_string());
+            }
+        };
+
+        let result = json!({ "context": { "slot": slot }, "value": value });
+        let body = json!({ "jsonrpc": "2.0", "id": request["id"], "result": result });
+        (200, body.to_string())
+    })
+    .await
+}
+
+/// Directory the devnet Pyth accounts are recorded to, with `record-fixtures --pyth`.
+pub(crate) fn pyth_devnet_fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/pyth/devnet")
+}
+
+/// Every account in `dir`, by file name.
+pub(crate) fn load_fixtures(dir: &Path) -> Vec<(String, AccountFixture)> {
+    let mut fixtures: Vec<(String, AccountFixture)> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension() == Some("json".as_ref()))
+        .map(|path| {
+            let name = path.file_stem().unwrap().to_string_lossy().to_string();
+            (
+                name,
+                AccountFixture::from_json(&fs::read_to_string(&path).unwrap()),
+            )
+        })
+        .collect();
+    fixtures.sort_by(|a, b| a.0.cmp(&b.0));
+
+    fixtures
+}
+
+/// Devnet Pyth mapping with its products and the BTC, ETH and SOL price accounts.
+pub(crate) fn pyth_devnet_fixtures() -> Vec<AccountFixture> {
+    load_fixtures(&pyth_devnet_fixtures_dir())
+        .into_iter()
+        .map(|(_, fixture)| fixture)
+        .collect()
+}
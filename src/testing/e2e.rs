@@ -0,0 +1,423 @@
+//! Signed interactions driven through `Context::handle_http_request`, as Discord sends them.
+//!
+//! Responses are compared with `tests/snapshots/<name>.json`, rerun with `UPDATE_SNAPSHOTS=1`
+//! to write them after an intended change.
+
+use std::{collections::HashMap, env, fs, mem, path::PathBuf, rc::Rc, str::FromStr};
+
+use chrono::{Duration, Utc};
+use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signer};
+use serde_json::{json, Value};
+
+use crate::{
+    catboard::{history::record_daily_price, indicators::record_indicator_summary},
+    context::Context,
+    endpoints::Endpoints,
+    http::{HttpRequest, HttpResponse},
+    kv::{bindings::Bindings, keys},
+    marinade::state::MSOL_PAIR,
+    solana::{
+        pubkey::Pubkey,
+        token::{TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID},
+        web3::Cluster,
+    },
+    task::run_tasks,
+    testing::{
+        pyth_devnet_fixtures, start_rpc_server, AccountFixture, MockServer, RecordedRequest,
+    },
+};
+
+const TIMESTAMP: &str = "1640995200";
+/// Wallet of `/wallet`, built by `wallet_fixtures`.
+const WALLET: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+const SYSTEM_PROGRAM_ID: &str = "11111111111111111111111111111111";
+
+/// Mint account with `decimals` under `program_id`.
+fn mint_fixture(mint: &str, program_id: &str, decimals: u8) -> AccountFixture {
+    let mut data = vec![0u8; 82];
+    data[44] = decimals;
+    data[45] = 1;
+
+    AccountFixture::new(mint, program_id, 1_461_600, &data)
+}
+
+/// Token account `n` of `owner` holding raw `amount` of `mint`.
+fn token_account_fixture(
+    n: u8,
+    owner: &str,
+    mint: &str,
+    program_id: &str,
+    amount: u64,
+) -> AccountFixture {
+    let mut data = Vec::with_capacity(165);
+    data.extend_from_slice(Pubkey::from_str(mint).unwrap().as_ref());
+    data.extend_from_slice(Pubkey::from_str(owner).unwrap().as_ref());
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.resize(165, 0);
+
+    AccountFixture::new(&Pubkey([n; 32]).to_string(), program_id, 2_039_280, &data)
+}
+
+/// `WALLET` with 2.5 SOL, 0.5 wrapped SOL and 0.1 ETH as SPL tokens, and 10 PYUSD on Token-2022.
+fn wallet_fixtures() -> Vec<AccountFixture> {
+    let wsol = "So11111111111111111111111111111111111111112";
+    let eth = "7vfCXTUXx5WJV5JADk17DUJ4ksgau7utNKj4b963voxs";
+    let pyusd = "2b1kV6DkPAnxd5ixfnxCpjxmKwqjjaYmCZfHsFu24GXo";
+
+    vec![
+        AccountFixture::new(WALLET, SYSTEM_PROGRAM_ID, 2_500_000_000, &[]),
+        mint_fixture(wsol, TOKEN_PROGRAM_ID, 9),
+        mint_fixture(eth, TOKEN_PROGRAM_ID, 8),
+        mint_fixture(pyusd, TOKEN_2022_PROGRAM_ID, 6),
+        token_account_fixture(1, WALLET, wsol, TOKEN_PROGRAM_ID, 500_000_000),
+        token_account_fixture(2, WALLET, eth, TOKEN_PROGRAM_ID, 10_000_000),
+        token_account_fixture(3, WALLET, pyusd, TOKEN_2022_PROGRAM_ID, 10_000_000),
+        // Someone else's
+        token_account_fixture(4, SYSTEM_PROGRAM_ID, eth, TOKEN_PROGRAM_ID, 1),
+    ]
+}
+
+/// Bot with its own key, KV, Solana RPC and Discord API, answering signed interactions.
+pub(crate) struct Harness {
+    keypair: Keypair,
+    pub(crate) bindings: Rc<Bindings>,
+    /// Worker env pointing every endpoint at the mock servers.
+    env: HashMap<String, String>,
+    pub(crate) endpoints: Endpoints,
+    _rpc: MockServer,
+    discord: MockServer,
+    _jupiter: MockServer,
+}
+
+impl Harness {
+    /// Pyth devnet prices of BTC, ETH and SOL, Marinade state, the Francium USDC pool, a wallet
+    /// and Jupiter quoting SOL to USDC.
+    pub(crate) async fn start() -> Harness {
+        let mut fixtures = pyth_devnet_fixtures();
+        fixtures.push(AccountFixture::from_json(include_str!(
+            "../../tests/fixtures/marinade/state.json"
+        )));
+        fixtures.push(AccountFixture::from_json(include_str!(
+            "../../tests/fixtures/francium/usdc_pool.json"
+        )));
+        fixtures.extend(wallet_fixtures());
+
+        let rpc = start_rpc_server(fixtures).await;
+        let discord = MockServer::start(|_request| (200, "{}".to_string())).await;
+        let jupiter = MockServer::start(|_request| {
+            (
+                200,
+                include_str!("../../tests/fixtures/jupiter/quote_sol_usdc.json").to_string(),
+            )
+        })
+        .await;
+
+        let secret = SecretKey::from_bytes(&[7; 32]).unwrap();
+        let public = PublicKey::from(&secret);
+        let env: HashMap<String, String> = [
+            ("PUBLIC_KEY", hex::encode(public.as_bytes())),
+            ("SOLANA_RPC_URL", rpc.url()),
+            ("DISCORD_API_URL", discord.url()),
+            ("JUPITER_QUOTE_API_URL", jupiter.url()),
+        ]
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.clone()))
+        .collect();
+        let harness = Harness {
+            keypair: Keypair { secret, public },
+            bindings: Rc::new(Bindings::in_memory()),
+            endpoints: Endpoints::from_env(&env),
+            env,
+            _rpc: rpc,
+            discord,
+            _jupiter: jupiter,
+        };
+
+        // As left by the product sync
+        let products = harness.bindings.products(&Cluster::Devnet);
+        let pairs = [
+            (
+                "Crypto.BTC/USD",
+                "HovQMDrbAgAYPCmHVSrezcSmkMtXSSUsLDFANExrZh2J",
+            ),
+            (
+                "Crypto.ETH/USD",
+                "EdVCmQ9FSPcVe5YySXDPCRmc8aDQLKJ9xvYBMZPie1Vw",
+            ),
+            (
+                "Crypto.SOL/USD",
+                "J83w4HKfqxwcq3BEMMkPFSppX3gqekLyLJBexebFVkix",
+            ),
+        ];
+        for (pair, px_pkey) in pairs.iter() {
+            products
+                .put_text(&keys::price_account(pair), px_pkey, 60)
+                .await
+                .unwrap();
+        }
+        products
+            .put_text(
+                keys::SYMBOLS,
+                "Crypto.BTC/USD,Crypto.ETH/USD,Crypto.SOL/USD",
+                60,
+            )
+            .await
+            .unwrap();
+
+        harness
+    }
+
+    /// Signature of `timestamp` followed by `body`, hex encoded as in `X-Signature-Ed25519`.
+    pub(crate) fn sign(&self, timestamp: &str, body: &str) -> String {
+        let message = format!("{}{}", timestamp, body);
+        hex::encode(self.keypair.sign(message.as_bytes()).to_bytes())
+    }
+
+    /// Context of a POST to the interactions endpoint, with `signature` as sent.
+    pub(crate) fn context(&self, body: &str, signature: &str) -> Context {
+        let headers: HashMap<String, String> = [
+            ("content-type", "application/json"),
+            ("x-signature-ed25519", signature),
+            ("x-signature-timestamp", TIMESTAMP),
+        ]
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+
+        Context {
+            env: self.env.clone(),
+            request: HttpRequest {
+                method: "POST".to_string(),
+                url: "https://catboard.workers.dev/".to_string(),
+                headers,
+                body: body.to_string(),
+            },
+        }
+    }
+
+    /// Response to a signed `interaction`.
+    pub(crate) async fn interact(&self, interaction: &Value) -> HttpResponse {
+        let body = interaction.to_string();
+        let context = self.context(&body, &self.sign(TIMESTAMP, &body));
+
+        context.handle_http_request(&self.bindings).await
+    }
+
+    /// Response to `/name` with `options`, invoked in a guild channel.
+    pub(crate) async fn command_response(&self, name: &str, options: Value) -> HttpResponse {
+        let response = self
+            .interact(&json!({
+                "type": 2,
+                "id": "100",
+                "application_id": "200",
+                "token": "interaction-token",
+                "guild_id": "300",
+                "channel_id": "400",
+                "member": { "user": { "id": "500", "username": "cat" } },
+                "data": { "id": "600", "name": name, "options": options },
+            }))
+            .await;
+        assert_eq!(response.status, 200, "/{} answered {}", name, response.body);
+
+        response
+    }
+
+    /// Run the background tasks of `response`, as `waitUntil` would, and return what they posted
+    /// to Discord.
+    pub(crate) async fn run_tasks(&self, response: &mut HttpResponse) -> Vec<RecordedRequest> {
+        let posted = self.discord.requests().len();
+        run_tasks(mem::take(&mut response.tasks), self.bindings.clone())
+            .await
+            .unwrap();
+
+        self.discord.requests().split_off(posted)
+    }
+
+    /// JSON body of the response to `/name`.
+    pub(crate) async fn command(&self, name: &str, options: Value) -> Value {
+        let response = self.command_response(name, options).await;
+
+        serde_json::from_str(&response.body).unwrap()
+    }
+}
+
+/// Compare `value` with `tests/snapshots/<name>.json`, or write it there with `UPDATE_SNAPSHOTS=1`.
+pub(crate) fn assert_snapshot(name: &str, value: &Value) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{}.json", name));
+    let actual = serde_json::to_string_pretty(value).unwrap() + "\n";
+
+    if env::var("UPDATE_SNAPSHOTS").is_ok() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!(
+            "{} is missing, run with UPDATE_SNAPSHOTS=1 to write it",
+            path.display()
+        )
+    });
+    assert_eq!(actual, expected, "{} changed", path.display());
+}
+
+fn string(name: &str, value: &str) -> Value {
+    json!({ "name": name, "type": 3, "value": value })
+}
+
+fn number(name: &str, value: f64) -> Value {
+    json!({ "name": name, "type": 10, "value": value })
+}
+
+fn sub_command(name: &str, options: Vec<Value>) -> Value {
+    json!([{ "name": name, "type": 1, "options": options }])
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_signed_requests() {
+    let harness = Harness::start().await;
+    let ping = json!({ "type": 1, "id": "100", "application_id": "200", "token": "t" }).to_string();
+
+    // Ping
+    let signed = harness.context(&ping, &harness.sign(TIMESTAMP, &ping));
+    let response = signed.handle_http_request(&harness.bindings).await;
+    assert_eq!(
+        (response.status, response.body.as_str()),
+        (200, r#"{"type":1,"data":null}"#)
+    );
+
+    // Tampered body, someone else's signature, no signature
+    let tampered = harness.context(&ping.replace("200", "201"), &harness.sign(TIMESTAMP, &ping));
+    let forged = harness.context(&ping, &"ab".repeat(64));
+    let mut unsigned = harness.context(&ping, "");
+    unsigned.request.headers.remove("x-signature-ed25519");
+    for (context, status) in [(tampered, 401), (forged, 401), (unsigned, 400)] {
+        let response = context.handle_http_request(&harness.bindings).await;
+        assert_eq!(response.status, status, "{}", response.body);
+    }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_command_snapshots() {
+    let harness = Harness::start().await;
+    let today = Utc::now().naive_utc().date();
+    let prices = harness.bindings.prices(&Cluster::Devnet);
+    for (days_ago, price) in [(2, 138.0), (1, 145.5), (0, 141.53)] {
+        record_daily_price(
+            prices,
+            "Crypto.SOL/USD",
+            today - Duration::days(days_ago),
+            price,
+        )
+        .await;
+    }
+    // As left by the price sync
+    record_indicator_summary(prices, "Crypto.SOL/USD", today, 141.53).await;
+    // mSOL 29 days ago, for the APY of `/stake`
+    record_daily_price(
+        harness.bindings.prices(&Cluster::MainnetBeta),
+        MSOL_PAIR,
+        today - Duration::days(29),
+        1.173,
+    )
+    .await;
+
+    let commands = [
+        ("hello", json!([])),
+        ("price", json!([string("symbol", "sol")])),
+        (
+            "chart",
+            json!([string("symbol", "SOL"), string("range", "7d")]),
+        ),
+        ("indicators", json!([string("symbol", "SOL")])),
+        (
+            "convert",
+            json!([
+                number("amount", 2.0),
+                string("from", "SOL"),
+                string("to", "ETH")
+            ]),
+        ),
+        (
+            "alert",
+            sub_command(
+                "add",
+                vec![
+                    string("symbol", "SOL"),
+                    string("direction", "above"),
+                    number("price", 150.0),
+                ],
+            ),
+        ),
+        (
+            "watchlist",
+            sub_command("add", vec![string("symbol", "btc")]),
+        ),
+        (
+            "swap_quote",
+            json!([
+                string("from", "SOL"),
+                string("to", "USDC"),
+                number("amount", 1.0)
+            ]),
+        ),
+        ("stake", json!([])),
+        ("lend", json!([string("asset", "usdc")])),
+        ("wallet", json!([string("address", WALLET)])),
+        ("help", json!([string("command", "price")])),
+        ("about", json!([])),
+    ];
+    for (name, options) in commands.iter() {
+        let response = harness.command(name, options.clone()).await;
+        assert_snapshot(name, &response);
+    }
+
+    // Replies to what can't be served
+    let failures = [
+        (
+            "swap_quote_unknown_token",
+            "swap_quote",
+            json!([
+                string("from", "DOGE"),
+                string("to", "USDC"),
+                number("amount", 1.0)
+            ]),
+        ),
+        (
+            "wallet_invalid_address",
+            "wallet",
+            json!([string("address", "not-a-wallet")]),
+        ),
+    ];
+    for (snapshot, name, options) in failures.iter() {
+        let response = harness.command(name, options.clone()).await;
+        assert_snapshot(snapshot, &response);
+    }
+
+    // Charts are rendered and uploaded in the follow-up, ephemeral when the deferral is
+    let mut response = harness
+        .command_response(
+            "chart",
+            json!([string("symbol", "SOL"), string("visibility", "ephemeral")]),
+        )
+        .await;
+    let body: Value = serde_json::from_str(&response.body).unwrap();
+    assert_eq!(body, json!({ "type": 5, "data": { "flags": 64 } }));
+    let posted = harness.run_tasks(&mut response).await;
+    assert_eq!(posted.len(), 1);
+    assert_eq!(posted[0].path, "/webhooks/200/interaction-token");
+    assert!(posted[0].body.contains("📈 `SOL` last `30d`"));
+    assert!(posted[0].body.contains(r#"filename="SOL.png""#));
+
+    // State left by the commands above
+    let response = harness
+        .command("watchlist", sub_command("show", vec![]))
+        .await;
+    assert_snapshot("watchlist_show", &response);
+    let response = harness.command("alert", sub_command("list", vec![])).await;
+    assert_snapshot("alert_list", &response);
+}
@@ -1,12 +1,13 @@
 addEventListener('fetch', (event) => {
-  event.respondWith(handleRequest(event.request))
+  event.respondWith(handleRequest(event))
 })
 
 /**
  * Fetch and log a request
- * @param {Request} request
+ * @param {FetchEvent} event
  */
-async function handleRequest(request) {
+async function handleRequest(event) {
+  const { request } = event
   try {
     const { wasm_main } = wasm_bindgen
 
@@ -27,17 +28,32 @@ async function handleRequest(request) {
         body: await request.text()
       },
       env: {
-        PUBLIC_KEY
+        PUBLIC_KEY,
+        // Optional, alerts are only posted when set
+        ...(typeof DISCORD_TOKEN !== 'undefined' && { DISCORD_TOKEN }),
+        // Optional, `/api/migrate_kv` is refused without it
+        ...(typeof API_KEY !== 'undefined' && { API_KEY })
       }
     }
 
-    const { status, body } = await wasm_main(context, DEVNET_PYTH_PRODUCTS)
+    // noinspection JSUnresolvedVariable
+    const bindings = {
+      DEVNET_PYTH_PRODUCTS,
+      DEVNET_PYTH_PRICES,
+      MAINNETBETA_PYTH_PRODUCTS,
+      MAINNETBETA_PYTH_PRICES,
+      USERS
+    }
+
+    // Follow-ups are sent after the response, keep the worker alive for them
+    const waitUntil = (promise) => event.waitUntil(promise)
+    const { status, body, bytes, content_type } = await wasm_main(context, bindings, waitUntil)
 
     // TODO: workaround for "{\"foo\": \"bar\"}"
-    return new Response(body, {
+    return new Response(bytes ? new Uint8Array(bytes) : body, {
       status,
       headers: {
-        'Content-Type': 'application/json'
+        'Content-Type': content_type || 'application/json'
       }
     })
   } catch (e) {